@@ -3,19 +3,56 @@ extern crate clap;
 extern crate pretty_env_logger;
 extern crate termcolor;
 
+use chrono::NaiveDate;
 use clap::ArgAction;
 use std::io;
+use std::io::IsTerminal;
 
+use eureka::browse::TerminalEvents;
 use eureka::config_manager::{ConfigManagement, ConfigManager, ConfigType};
-use eureka::git::Git;
+use eureka::git::{Git, HttpsToken};
+use eureka::idea::Status;
 use eureka::printer::Printer;
 use eureka::program_access::ProgramAccess;
-use eureka::reader::Reader;
-use eureka::{Eureka, EurekaOptions};
+use eureka::reader::{read_body, Reader};
+use eureka::{Eureka, EurekaCommand, EurekaOptions};
 use log::error;
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
 
 const ARG_CLEAR_CONFIG: &str = "clear-config";
 const ARG_VIEW: &str = "view";
+const ARG_ALL: &str = "all";
+const ARG_RENDER: &str = "render";
+const ARG_SUMMARY: &str = "summary";
+const ARG_MESSAGE: &str = "message";
+const ARG_BODY: &str = "body";
+const ARG_NO_EDITOR: &str = "no-editor";
+const ARG_TAG: &str = "tag";
+
+const ARG_SINCE: &str = "since";
+const ARG_LIMIT: &str = "limit";
+const ARG_REVERSE: &str = "reverse";
+const ARG_QUERY: &str = "query";
+const ARG_REGEX: &str = "regex";
+const ARG_CASE_SENSITIVE: &str = "case-sensitive";
+const ARG_ID: &str = "id";
+const ARG_FORCE: &str = "force";
+const ARG_PATH: &str = "path";
+const ARG_REMOTE_URL: &str = "remote-url";
+
+const CMD_SYNC: &str = "sync";
+const CMD_INIT: &str = "init";
+const CMD_DOCTOR: &str = "doctor";
+const CMD_LIST: &str = "list";
+const CMD_SEARCH: &str = "search";
+const CMD_SHOW: &str = "show";
+const CMD_EDIT: &str = "edit";
+const CMD_UNDO: &str = "undo";
+const CMD_START: &str = "start";
+const CMD_DONE: &str = "done";
+const CMD_DROP: &str = "drop";
+const CMD_BROWSE: &str = "browse";
 
 fn main() {
     pretty_env_logger::init();
@@ -37,26 +74,301 @@ fn main() {
                 .action(ArgAction::SetTrue)
                 .help("View ideas with your $PAGER env variable. If unset use less"),
         )
+        .arg(
+            clap::Arg::new(ARG_ALL)
+                .long(ARG_ALL)
+                .short('a')
+                .action(ArgAction::SetTrue)
+                .requires(ARG_VIEW)
+                .help("With --view, also show ideas that are done or dropped"),
+        )
+        .arg(
+            clap::Arg::new(ARG_RENDER)
+                .long(ARG_RENDER)
+                .short('R')
+                .action(ArgAction::SetTrue)
+                .requires(ARG_VIEW)
+                .help("With --view, print the ideas as rendered markdown instead of using $PAGER"),
+        )
+        .arg(
+            clap::Arg::new(ARG_SUMMARY)
+                .value_name("SUMMARY")
+                .conflicts_with(ARG_MESSAGE)
+                .help("Capture an idea with this summary without opening $EDITOR"),
+        )
+        .arg(
+            clap::Arg::new(ARG_MESSAGE)
+                .long(ARG_MESSAGE)
+                .short('m')
+                .value_name("SUMMARY")
+                .help("Capture an idea with this summary without opening $EDITOR"),
+        )
+        .arg(
+            clap::Arg::new(ARG_BODY)
+                .long(ARG_BODY)
+                .short('b')
+                .value_name("BODY")
+                .help("Body of the idea, or - to read it from stdin"),
+        )
+        .arg(
+            clap::Arg::new(ARG_NO_EDITOR)
+                .long(ARG_NO_EDITOR)
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all([ARG_SUMMARY, ARG_MESSAGE, ARG_VIEW])
+                .help("Type the body of the idea at the prompt instead of opening $EDITOR"),
+        )
+        .arg(
+            clap::Arg::new(ARG_TAG)
+                .long(ARG_TAG)
+                .short('t')
+                .value_name("TAG")
+                .action(ArgAction::Append)
+                .help("Tag the idea. With --view, only show ideas with this tag. Can be repeated"),
+        )
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            clap::Command::new(CMD_SYNC).about("Push ideas that could not be pushed when captured"),
+        )
+        .subcommand(
+            clap::Command::new(CMD_INIT)
+                .about("Create a new ideas repo and start capturing ideas to it")
+                .arg(
+                    clap::Arg::new(ARG_PATH)
+                        .value_name("PATH")
+                        .help("Directory of the new repo, created if missing. Defaults to the current directory"),
+                )
+                .arg(
+                    clap::Arg::new(ARG_REMOTE_URL)
+                        .long(ARG_REMOTE_URL)
+                        .value_name("URL")
+                        .help("Add a remote with this URL to push ideas to"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new(CMD_DOCTOR)
+                .about("Check the config, repo, remote, ssh key and programs eureka uses"),
+        )
+        .subcommand(
+            clap::Command::new(CMD_LIST)
+                .about("List captured ideas, newest first")
+                .arg(
+                    clap::Arg::new(ARG_SINCE)
+                        .long(ARG_SINCE)
+                        .value_name("YYYY-MM-DD")
+                        .value_parser(parse_date)
+                        .help("Only list ideas captured on or after this date"),
+                )
+                .arg(
+                    clap::Arg::new(ARG_LIMIT)
+                        .long(ARG_LIMIT)
+                        .short('n')
+                        .value_name("NUMBER")
+                        .value_parser(value_parser!(usize))
+                        .help("List at most this many ideas"),
+                )
+                .arg(
+                    clap::Arg::new(ARG_REVERSE)
+                        .long(ARG_REVERSE)
+                        .short('r')
+                        .action(ArgAction::SetTrue)
+                        .help("List the oldest ideas first"),
+                ),
+        )
+        .subcommand(
+            clap::Command::new(CMD_SEARCH)
+                .about("Search the summaries and bodies of your ideas")
+                .arg(
+                    clap::Arg::new(ARG_QUERY)
+                        .value_name("QUERY")
+                        .required(true)
+                        .num_args(1..)
+                        .allow_hyphen_values(true)
+                        .help("Terms that must all match. Use OR between alternatives and -term or NOT term to exclude"),
+                )
+                .arg(
+                    clap::Arg::new(ARG_REGEX)
+                        .long(ARG_REGEX)
+                        .short('e')
+                        .action(ArgAction::SetTrue)
+                        .help("Match the terms as regular expressions"),
+                )
+                .arg(
+                    clap::Arg::new(ARG_CASE_SENSITIVE)
+                        .long(ARG_CASE_SENSITIVE)
+                        .short('s')
+                        .action(ArgAction::SetTrue)
+                        .help("Match the case of the terms"),
+                ),
+        )
+        .subcommand(id_command(CMD_SHOW, "Print an idea"))
+        .subcommand(id_command(CMD_EDIT, "Revise an idea with $EDITOR"))
+        .subcommand(
+            clap::Command::new(CMD_UNDO)
+                .about("Revert the last commit, or remove it if it wasn't pushed yet")
+                .arg(
+                    clap::Arg::new(ARG_FORCE)
+                        .long(ARG_FORCE)
+                        .short('f')
+                        .action(ArgAction::SetTrue)
+                        .help("Also undo the last commit if it wasn't made by eureka"),
+                ),
+        )
+        .subcommand(id_command(CMD_START, "Mark an idea as in progress"))
+        .subcommand(id_command(CMD_DONE, "Mark an idea as done"))
+        .subcommand(id_command(CMD_DROP, "Mark an idea as dropped"))
+        .subcommand(
+            clap::Command::new(CMD_BROWSE)
+                .about("Browse, filter and preview ideas in a full-screen terminal UI"),
+        )
         .get_matches();
 
+    let summary = cli_flags
+        .get_one::<String>(ARG_SUMMARY)
+        .or_else(|| cli_flags.get_one::<String>(ARG_MESSAGE))
+        .cloned();
+    let body = match read_body(
+        cli_flags.get_one::<String>(ARG_BODY).map(String::as_str),
+        io::stdin(),
+    ) {
+        Ok(body) => body,
+        Err(e) => {
+            error!("{}", e);
+            return;
+        }
+    };
+
     let stdio = io::stdin();
-    let input = stdio.lock();
+    let reader = match stdio.is_terminal() {
+        true => Reader::terminal(stdio.lock()),
+        false => Reader::new(stdio.lock()),
+    };
     let output = termcolor::StandardStream::stdout(termcolor::ColorChoice::Always);
 
-    let config = ConfigManager::default();
+    let config = ConfigManager;
     let ssh_key = config.config_read(ConfigType::SshKey).unwrap_or_default();
+    // A token command wins over the env variable
+    let https_token = match (
+        config
+            .config_read(ConfigType::HttpsTokenCommand)
+            .unwrap_or_default(),
+        config
+            .config_read(ConfigType::HttpsTokenEnv)
+            .unwrap_or_default(),
+    ) {
+        (command, _) if !command.is_empty() => Some(HttpsToken::Command(command)),
+        (_, name) if !name.is_empty() => Some(HttpsToken::Env(name)),
+        _ => None,
+    };
 
     let mut eureka = Eureka::new(
-        ConfigManager::default(),
+        ConfigManager,
         Printer::new(output),
-        Reader::new(input),
-        Git::new(&ssh_key),
-        ProgramAccess::default(),
+        reader,
+        Git::new(
+            &ssh_key,
+            &config
+                .config_read(ConfigType::HttpsUsername)
+                .unwrap_or_default(),
+            https_token,
+        ),
+        ProgramAccess::new(
+            &config.config_read(ConfigType::Editor).unwrap_or_default(),
+            &config.config_read(ConfigType::Pager).unwrap_or_default(),
+        ),
     );
 
+    // The browser takes over the terminal instead of running a single command
+    if let Some((CMD_BROWSE, _)) = cli_flags.subcommand() {
+        // The first time setup asks its questions before the terminal is taken over
+        if eureka.is_config_missing() {
+            if let Err(e) = eureka.first_time_setup() {
+                error!("{}", e);
+            }
+            return;
+        }
+        if let Err(e) = TerminalEvents::new().and_then(|mut events| {
+            let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+            eureka.browse(&mut terminal, &mut events)
+        }) {
+            error!("{}", e);
+        }
+        return;
+    }
+
+    let command = match cli_flags.subcommand() {
+        Some((CMD_SYNC, _)) => Some(EurekaCommand::Sync),
+        Some((CMD_DOCTOR, _)) => Some(EurekaCommand::Doctor),
+        Some((CMD_INIT, init_flags)) => {
+            // Relative paths are relative to where eureka runs, like with `git init`
+            let path = match std::env::current_dir() {
+                Ok(current_dir) => match init_flags.get_one::<String>(ARG_PATH) {
+                    Some(path) => current_dir.join(path),
+                    None => current_dir,
+                },
+                Err(e) => {
+                    error!("{}", e);
+                    return;
+                }
+            };
+            Some(EurekaCommand::Init {
+                path,
+                remote_url: init_flags.get_one::<String>(ARG_REMOTE_URL).cloned(),
+            })
+        }
+        Some((CMD_LIST, list_flags)) => Some(EurekaCommand::List {
+            since: list_flags.get_one::<NaiveDate>(ARG_SINCE).copied(),
+            limit: list_flags.get_one::<usize>(ARG_LIMIT).copied(),
+            reverse: list_flags.get_flag(ARG_REVERSE),
+        }),
+        Some((CMD_SHOW, show_flags)) => Some(EurekaCommand::Show {
+            id: show_flags.get_one::<String>(ARG_ID).unwrap().clone(),
+        }),
+        Some((CMD_EDIT, edit_flags)) => Some(EurekaCommand::Edit {
+            id: edit_flags.get_one::<String>(ARG_ID).unwrap().clone(),
+        }),
+        Some((CMD_UNDO, undo_flags)) => Some(EurekaCommand::Undo {
+            force: undo_flags.get_flag(ARG_FORCE),
+        }),
+        Some((CMD_START, status_flags)) => Some(EurekaCommand::SetStatus {
+            id: status_flags.get_one::<String>(ARG_ID).unwrap().clone(),
+            status: Status::InProgress,
+        }),
+        Some((CMD_DONE, status_flags)) => Some(EurekaCommand::SetStatus {
+            id: status_flags.get_one::<String>(ARG_ID).unwrap().clone(),
+            status: Status::Done,
+        }),
+        Some((CMD_DROP, status_flags)) => Some(EurekaCommand::SetStatus {
+            id: status_flags.get_one::<String>(ARG_ID).unwrap().clone(),
+            status: Status::Dropped,
+        }),
+        Some((CMD_SEARCH, search_flags)) => Some(EurekaCommand::Search {
+            query: search_flags
+                .get_many::<String>(ARG_QUERY)
+                .unwrap_or_default()
+                .map(|term| quote_phrase(term))
+                .collect::<Vec<String>>()
+                .join(" "),
+            is_regex: search_flags.get_flag(ARG_REGEX),
+            is_case_sensitive: search_flags.get_flag(ARG_CASE_SENSITIVE),
+        }),
+        _ => None,
+    };
+
     let opts = EurekaOptions {
         clear_config: cli_flags.get_flag(ARG_CLEAR_CONFIG),
         view: cli_flags.get_flag(ARG_VIEW),
+        all: cli_flags.get_flag(ARG_ALL),
+        render: cli_flags.get_flag(ARG_RENDER),
+        page_height: page_height(),
+        command,
+        summary,
+        body,
+        no_editor: cli_flags.get_flag(ARG_NO_EDITOR),
+        tags: cli_flags
+            .get_many::<String>(ARG_TAG)
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
     };
 
     match eureka.run(opts) {
@@ -64,3 +376,36 @@ fn main() {
         Err(e) => error!("{}", e),
     }
 }
+
+// Rendered ideas are only paginated when they are printed to a terminal
+fn page_height() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(_, rows)| usize::from(rows))
+}
+
+fn id_command(name: &'static str, about: &'static str) -> clap::Command {
+    clap::Command::new(name).about(about).arg(
+        clap::Arg::new(ARG_ID)
+            .value_name("ID")
+            .required(true)
+            .help("Id of the idea, as shown by `eureka list`. A prefix is enough"),
+    )
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("\"{}\" is not a date, use YYYY-MM-DD", value))
+}
+
+// An argument with whitespace, e.g. `eureka search "some phrase"`, is searched as a phrase
+fn quote_phrase(term: &str) -> String {
+    if term.contains(char::is_whitespace) && !term.contains('"') {
+        format!("\"{}\"", term)
+    } else {
+        term.to_string()
+    }
+}
@@ -0,0 +1,452 @@
+use std::io;
+use std::io::Write;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::idea::{Idea, Status};
+use crate::render;
+
+const HELP: &str = "↑/↓ select  Enter edit  Ctrl-D done  Ctrl-Y copy  Esc quit";
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Where `eureka browse` gets its keys from and hands the terminal over to other
+/// programs, so the browser can be driven without a terminal
+pub trait BrowseEvents {
+    fn next_key(&mut self) -> io::Result<KeyEvent>;
+    // Gives the terminal back, e.g. while $EDITOR runs
+    fn suspend(&mut self) -> io::Result<()>;
+    fn resume(&mut self) -> io::Result<()>;
+    fn copy(&mut self, text: &str) -> io::Result<()>;
+}
+
+/// Reads keys from the terminal, which is in raw mode on the alternate screen
+/// until this is dropped
+pub struct TerminalEvents;
+
+impl TerminalEvents {
+    pub fn new() -> io::Result<Self> {
+        let mut events = Self;
+        events.resume()?;
+        Ok(events)
+    }
+}
+
+impl Drop for TerminalEvents {
+    fn drop(&mut self) {
+        if let Err(err) = self.suspend() {
+            error!("Could not restore the terminal: {}", err);
+        }
+    }
+}
+
+impl BrowseEvents for TerminalEvents {
+    fn next_key(&mut self) -> io::Result<KeyEvent> {
+        loop {
+            if let Event::Key(key) = event::read()? {
+                // Windows also reports key releases
+                if key.kind == KeyEventKind::Press {
+                    return Ok(key);
+                }
+            }
+        }
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        crossterm::execute!(io::stdout(), LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        terminal::enable_raw_mode()?;
+        crossterm::execute!(io::stdout(), EnterAlternateScreen)
+    }
+
+    // Asks the terminal to copy the text with an OSC 52 escape sequence, which
+    // also works over ssh
+    fn copy(&mut self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+        stdout.flush()
+    }
+}
+
+/// What the browser wants done after a key press
+#[derive(Debug, Eq, PartialEq)]
+pub enum Action {
+    None,
+    Quit,
+    Edit(String),
+    Done(String),
+    Copy(String),
+}
+
+/// The state of `eureka browse`: the ideas, newest first, with their ids, and
+/// the ones matching the filter typed so far
+pub struct Browser {
+    ideas: Vec<(Option<String>, Idea)>,
+    filter: String,
+    selected: usize,
+    status: Option<String>,
+}
+
+impl Browser {
+    pub fn new(ideas: Vec<(Option<String>, Idea)>) -> Self {
+        Self {
+            ideas,
+            filter: String::new(),
+            selected: 0,
+            status: None,
+        }
+    }
+
+    /// Replaces the ideas after they changed, keeping the filter
+    pub fn set_ideas(&mut self, ideas: Vec<(Option<String>, Idea)>) {
+        self.ideas = ideas;
+        self.selected = self.selected.min(self.visible().len().saturating_sub(1));
+    }
+
+    /// Shown instead of the help until the next key press
+    pub fn set_status(&mut self, status: &str) {
+        self.status = Some(status.to_string());
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        self.status = None;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        match key.code {
+            KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('c') if ctrl => return Action::Quit,
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_selection(1),
+            KeyCode::Enter => return self.selected_action(Action::Edit),
+            KeyCode::Char('d') if ctrl => return self.selected_action(Action::Done),
+            KeyCode::Char('y') if ctrl => {
+                if let Some((_, idea)) = self.selected_idea() {
+                    return Action::Copy(idea.render());
+                }
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.filter.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [filter_area, main_area, footer_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.size());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main_area);
+
+        let filter = format!("> {}", self.filter);
+        frame.set_cursor(filter_area.x + filter.chars().count() as u16, filter_area.y);
+        frame.render_widget(Paragraph::new(filter), filter_area);
+
+        let visible = self.visible();
+        let items: Vec<ListItem> = visible
+            .iter()
+            .map(|(_, idea)| {
+                let style = if idea.status.is_closed() {
+                    Style::default().add_modifier(Modifier::DIM)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(format!("{}{}", status_marker(idea.status), idea.summary))
+                    .style(style)
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                " Ideas {}/{} ",
+                visible.len(),
+                self.ideas.len()
+            )))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut list_state = ListState::default().with_selected(if visible.is_empty() {
+            None
+        } else {
+            Some(self.selected)
+        });
+        frame.render_stateful_widget(list, list_area, &mut list_state);
+
+        let preview = match self.selected_idea() {
+            Some((_, idea)) => preview_text(idea),
+            None => Text::from("No ideas found"),
+        };
+        frame.render_widget(
+            Paragraph::new(preview)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(" Preview ")),
+            preview_area,
+        );
+
+        let footer = match &self.status {
+            Some(status) => Span::styled(status.as_str(), Style::default().fg(Color::Yellow)),
+            None => Span::styled(HELP, Style::default().add_modifier(Modifier::DIM)),
+        };
+        frame.render_widget(Paragraph::new(footer), footer_area);
+    }
+
+    // The ideas matching the filter, best matches first
+    fn visible(&self) -> Vec<&(Option<String>, Idea)> {
+        let mut scored: Vec<(usize, &(Option<String>, Idea))> = self
+            .ideas
+            .iter()
+            .filter_map(|entry| {
+                let text = format!("{} {}", entry.1.summary, entry.1.tags.join(" "));
+                fuzzy_score(&self.filter, &text).map(|score| (score, entry))
+            })
+            .collect();
+        // Stable, so equally good matches stay newest first
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    fn selected_idea(&self) -> Option<&(Option<String>, Idea)> {
+        self.visible().get(self.selected).copied()
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.visible().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(offset).min(last);
+    }
+
+    fn selected_action(&mut self, action: fn(String) -> Action) -> Action {
+        match self.selected_idea() {
+            Some((Some(id), _)) => action(id.clone()),
+            Some((None, _)) => {
+                self.set_status("This idea isn't committed yet, so it can't be changed");
+                Action::None
+            }
+            None => Action::None,
+        }
+    }
+}
+
+/// Scores how well the query matches the text, ignoring case and whitespace in
+/// the query. Every character of the query must appear in order in the text,
+/// characters that follow each other or start a word score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|c| *c == query_char)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 2;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 1;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+fn status_marker(status: Status) -> &'static str {
+    match status {
+        Status::Open => "  ",
+        Status::InProgress => "▸ ",
+        Status::Done => "✓ ",
+        Status::Dropped => "✗ ",
+    }
+}
+
+fn preview_text(idea: &Idea) -> Text<'static> {
+    render::render(&idea.render())
+        .into_iter()
+        .map(|spans| {
+            Line::from(
+                spans
+                    .into_iter()
+                    .map(|span| Span::styled(span.text, preview_style(span.style)))
+                    .collect::<Vec<Span>>(),
+            )
+        })
+        .collect::<Vec<Line>>()
+        .into()
+}
+
+// The colors match the ones `Printer::styled` uses
+fn preview_style(style: render::Style) -> Style {
+    let default = Style::default();
+    match style {
+        render::Style::Plain => default,
+        render::Style::Heading(1) => default.fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        render::Style::Heading(_) => default.fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        render::Style::Strong => default.add_modifier(Modifier::BOLD),
+        render::Style::Emphasis => default.add_modifier(Modifier::ITALIC),
+        render::Style::Code => default.fg(Color::Yellow),
+        render::Style::Bullet => default.fg(Color::Blue),
+        render::Style::Checked => default.fg(Color::Green),
+        render::Style::Unchecked => default.fg(Color::Red),
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | u32::from(*byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod tests {
+    use crate::browse::{base64, fuzzy_score, Action, Browser};
+    use crate::idea::{Idea, Status};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn idea(summary: &str, status: Status) -> Idea {
+        Idea {
+            id: None,
+            summary: summary.to_string(),
+            body: String::from("some-body"),
+            captured: None,
+            status,
+            tags: Vec::new(),
+        }
+    }
+
+    fn browser() -> Browser {
+        Browser::new(vec![
+            (
+                Some(String::from("ccc3333")),
+                idea("Write a blog post", Status::Open),
+            ),
+            (
+                Some(String::from("bbb2222")),
+                idea("Learn rust", Status::Done),
+            ),
+            (None, idea("Build a bike shed", Status::Open)),
+        ])
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn screen(browser: &Browser) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .chunks(60)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_browse__fuzzy_score() {
+        assert!(fuzzy_score("", "anything").is_some());
+        assert!(fuzzy_score("BLP", "write a blog post").is_some());
+        assert!(fuzzy_score("pb", "write a blog post").is_none());
+        // Consecutive characters at the start of words score higher
+        assert!(fuzzy_score("blog", "a blog") > fuzzy_score("blog", "b-l-o-g"));
+    }
+
+    #[test]
+    fn test_browse__handle_key__filter_and_select() {
+        let mut browser = browser();
+
+        for c in "bl".chars() {
+            assert_eq!(browser.handle_key(key(KeyCode::Char(c))), Action::None);
+        }
+        assert_eq!(
+            browser.handle_key(key(KeyCode::Enter)),
+            Action::Edit(String::from("ccc3333"))
+        );
+
+        browser.handle_key(key(KeyCode::Down));
+        browser.handle_key(key(KeyCode::Down));
+        assert_eq!(browser.handle_key(key(KeyCode::Enter)), Action::None);
+        // Ideas without an id can't be changed
+        assert!(screen(&browser).contains("isn't committed yet"));
+
+        browser.handle_key(key(KeyCode::Backspace));
+        browser.handle_key(key(KeyCode::Backspace));
+        browser.handle_key(ctrl('n'));
+        assert_eq!(
+            browser.handle_key(ctrl('d')),
+            Action::Done(String::from("bbb2222"))
+        );
+        assert_eq!(
+            browser.handle_key(ctrl('y')),
+            Action::Copy(String::from(
+                "## Learn rust\n\n_status: done_\n\nsome-body\n"
+            ))
+        );
+        assert_eq!(browser.handle_key(key(KeyCode::Esc)), Action::Quit);
+    }
+
+    #[test]
+    fn test_browse__draw() {
+        let mut browser = browser();
+        browser.handle_key(key(KeyCode::Char('r')));
+        browser.handle_key(key(KeyCode::Char('u')));
+
+        let actual = screen(&browser);
+
+        assert!(actual.starts_with("> ru"));
+        assert!(actual.contains(" Ideas 1/3 "));
+        assert!(actual.contains("✓ Learn rust"));
+        assert!(!actual.contains("Write a blog post"));
+        assert!(actual.contains("some-body"));
+        assert!(actual.contains("Esc quit"));
+    }
+
+    #[test]
+    fn test_browse__base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64("idée".as_bytes()), "aWTDqWU=");
+    }
+}
@@ -2,31 +2,84 @@ use crate::dirs::home_dir;
 
 use std::env::var;
 use std::io::{ErrorKind, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 use serde::{Deserialize, Serialize};
 
+use crate::storage::Layout;
+
 const CONFIG_FILE_NAME: &str = "config.json";
+const STATE_FILE_NAME: &str = "state.json";
+
+pub const DEFAULT_BRANCH: &str = "main";
+pub const DEFAULT_REMOTE: &str = "origin";
 
+// Fields are defaulted so config files written by older versions, which lack
+// some of the fields, can still be read
 #[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
 struct Config {
     repo: PathBuf,
+    #[serde(skip_serializing_if = "is_empty_path")]
     ssh_key: PathBuf,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    branch: String,
+    // An empty remote means ideas are only committed locally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    layout: String,
+    // Only used with the per-idea layout
+    #[serde(skip_serializing_if = "is_false")]
+    readme_index: bool,
+    // Override $VISUAL/$EDITOR and $PAGER, e.g. "code --wait"
+    #[serde(skip_serializing_if = "String::is_empty")]
+    editor: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pager: String,
+    // For HTTPS remotes, the token is read from the env variable or printed by
+    // the command, e.g. "pass show github/token"
+    #[serde(skip_serializing_if = "String::is_empty")]
+    https_username: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    https_token_env: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    https_token_command: String,
+}
+
+// State that eureka keeps between runs, stored next to the config
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct State {
+    // Ids of commits that have not been pushed yet
+    unpushed: Vec<String>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum ConfigType {
     Repo,
     SshKey,
+    Branch,
+    Remote,
+    Layout,
+    ReadmeIndex,
+    Editor,
+    Pager,
+    HttpsUsername,
+    HttpsTokenEnv,
+    HttpsTokenCommand,
 }
 
 pub trait ConfigManagement {
     fn config_dir_create(&self) -> io::Result<()>;
     fn config_dir_exists(&self) -> bool;
+    fn config_path(&self) -> io::Result<PathBuf>;
     fn config_read(&self, config_type: ConfigType) -> io::Result<String>;
     fn config_write(&self, config_type: ConfigType, value: String) -> io::Result<()>;
     fn config_rm(&self) -> io::Result<()>;
+    fn unpushed_read(&self) -> io::Result<Vec<String>>;
+    fn unpushed_write(&self, unpushed: Vec<String>) -> io::Result<()>;
 }
 
 #[derive(Default)]
@@ -41,11 +94,27 @@ impl ConfigManagement for ConfigManager {
         self.config_dir_path().and_then(fs::metadata).is_ok()
     }
 
+    fn config_path(&self) -> io::Result<PathBuf> {
+        Ok(self.config_dir_path()?.join(CONFIG_FILE_NAME))
+    }
+
     fn config_read(&self, config_type: ConfigType) -> io::Result<String> {
         let config = self.config()?;
         let config_value = match config_type {
             ConfigType::Repo => config.repo.display().to_string(),
             ConfigType::SshKey => config.ssh_key.display().to_string(),
+            // Config written before the branch was configurable has no branch
+            ConfigType::Branch if config.branch.is_empty() => DEFAULT_BRANCH.to_string(),
+            ConfigType::Branch => config.branch,
+            ConfigType::Remote => config.remote.unwrap_or_else(|| DEFAULT_REMOTE.to_string()),
+            ConfigType::Layout if config.layout.is_empty() => Layout::default().to_string(),
+            ConfigType::Layout => config.layout,
+            ConfigType::ReadmeIndex => config.readme_index.to_string(),
+            ConfigType::Editor => config.editor,
+            ConfigType::Pager => config.pager,
+            ConfigType::HttpsUsername => config.https_username,
+            ConfigType::HttpsTokenEnv => config.https_token_env,
+            ConfigType::HttpsTokenCommand => config.https_token_command,
         };
         Ok(config_value)
     }
@@ -65,10 +134,25 @@ impl ConfigManagement for ConfigManager {
         match config_type {
             ConfigType::Repo => config.repo = PathBuf::from(value),
             ConfigType::SshKey => config.ssh_key = PathBuf::from(value),
+            ConfigType::Branch => config.branch = value,
+            ConfigType::Remote => config.remote = Some(value),
+            ConfigType::Layout => config.layout = value,
+            ConfigType::ReadmeIndex => {
+                config.readme_index = value
+                    .parse()
+                    .map_err(|err| io::Error::new(ErrorKind::InvalidInput, err))?
+            }
+            ConfigType::Editor => config.editor = value,
+            ConfigType::Pager => config.pager = value,
+            ConfigType::HttpsUsername => config.https_username = value,
+            ConfigType::HttpsTokenEnv => config.https_token_env = value,
+            ConfigType::HttpsTokenCommand => config.https_token_command = value,
         }
 
         let json = serde_json::to_string(&config)?;
 
+        // The new content can be shorter than the existing one
+        file.set_len(0)?;
         file.write_all(json.as_bytes())
     }
 
@@ -78,11 +162,24 @@ impl ConfigManagement for ConfigManager {
         fs::metadata(&config_path)?;
         fs::remove_file(&config_path)
     }
+
+    fn unpushed_read(&self) -> io::Result<Vec<String>> {
+        Ok(self.state()?.unpushed)
+    }
+
+    fn unpushed_write(&self, unpushed: Vec<String>) -> io::Result<()> {
+        let mut state = self.state()?;
+        state.unpushed = unpushed;
+
+        let json = serde_json::to_string(&state)?;
+
+        fs::write(self.state_path()?, json)
+    }
 }
 
 impl ConfigManager {
-    fn config_path(&self) -> io::Result<PathBuf> {
-        Ok(self.config_dir_path()?.join(CONFIG_FILE_NAME))
+    fn state_path(&self) -> io::Result<PathBuf> {
+        Ok(self.config_dir_path()?.join(STATE_FILE_NAME))
     }
 
     fn config_dir_path(&self) -> io::Result<PathBuf> {
@@ -112,6 +209,16 @@ impl ConfigManager {
         Ok(serde_json::from_str(&contents)?)
     }
 
+    fn state(&self) -> io::Result<State> {
+        match fs::read_to_string(self.state_path()?) {
+            Ok(contents) if contents.is_empty() => Ok(State::default()),
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            // Nothing has been stored yet
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(State::default()),
+            Err(err) => Err(err),
+        }
+    }
+
     fn resolve_xdg_config_home(&self) -> Option<PathBuf> {
         match var("XDG_CONFIG_HOME") {
             Ok(path) => Some(PathBuf::from(path).join("eureka")),
@@ -120,6 +227,14 @@ impl ConfigManager {
     }
 }
 
+fn is_empty_path(path: &Path) -> bool {
+    path.as_os_str().is_empty()
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
 #[allow(non_snake_case)]
 #[cfg(test)]
 mod tests {
@@ -133,7 +248,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_dir_path() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (_config_dir, tmp_dir) = set_config_dir()?;
 
         // XDG_CONFIG_HOME is set in Github Actions so let's unset it
@@ -153,7 +268,7 @@ mod tests {
     fn test_config_manager__config_dir_path__when__xdg_config_home_env_var_set() -> TestResult {
         use std::path::Path;
 
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         env::set_var("XDG_CONFIG_HOME", "/specific-path/.config");
         assert_eq!(
             env::var("XDG_CONFIG_HOME"),
@@ -172,7 +287,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_dir_create() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (_config_dir, _tmp_dir) = set_config_dir()?;
 
         let actual = cm.config_dir_create();
@@ -185,7 +300,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_dir_exists__success() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (_config_dir, _tmp_dir) = set_and_create_config_dir()?;
 
         let config_dir_exists = cm.config_dir_exists();
@@ -198,7 +313,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_dir_exists__failure() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (_config_dir, _tmp_dir) = set_config_dir()?;
 
         // XDG_CONFIG_HOME is set in Github Actions so let's unset it
@@ -215,7 +330,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_read__success() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
         let mut file =
             fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
@@ -232,7 +347,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_read__file_is_empty__default_config() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
         // Create file but leave it empty
         let _file = fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
@@ -246,9 +361,145 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_config_manager__config_read__branch_is_missing__default_branch() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let mut file =
+            fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
+        file.write_all("{\"repo\": \"some-repo\", \"ssh_key\": \"some-key\"}".as_bytes())?;
+
+        let actual = cm.config_read(ConfigType::Branch)?;
+        let expected = "main";
+
+        env::remove_var("HOME");
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_manager__config_read__branch__success() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let mut file =
+            fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
+        file.write_all("{\"repo\": \"some-repo\", \"branch\": \"ideas\"}".as_bytes())?;
+
+        let actual = cm.config_read(ConfigType::Branch)?;
+        let expected = "ideas";
+
+        env::remove_var("HOME");
+
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_manager__config_read__remote() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let config_path = config_dir.join("config.json");
+
+        fs::write(&config_path, "{\"repo\": \"some-repo\"}")?;
+        let missing = cm.config_read(ConfigType::Remote)?;
+
+        fs::write(&config_path, "{\"repo\": \"some-repo\", \"remote\": \"\"}")?;
+        let local_only = cm.config_read(ConfigType::Remote)?;
+
+        fs::write(
+            &config_path,
+            "{\"repo\": \"some-repo\", \"remote\": \"upstream\"}",
+        )?;
+        let configured = cm.config_read(ConfigType::Remote)?;
+
+        env::remove_var("HOME");
+
+        assert_eq!(missing, "origin");
+        assert_eq!(local_only, "");
+        assert_eq!(configured, "upstream");
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_manager__config_read__layout() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let config_path = config_dir.join("config.json");
+
+        fs::write(&config_path, "{\"repo\": \"some-repo\"}")?;
+        let missing_layout = cm.config_read(ConfigType::Layout)?;
+        let missing_index = cm.config_read(ConfigType::ReadmeIndex)?;
+
+        cm.config_write(ConfigType::Layout, String::from("per-idea"))?;
+        cm.config_write(ConfigType::ReadmeIndex, String::from("true"))?;
+        let layout = cm.config_read(ConfigType::Layout)?;
+        let index = cm.config_read(ConfigType::ReadmeIndex)?;
+        let invalid_index = cm.config_write(ConfigType::ReadmeIndex, String::from("yes"));
+
+        env::remove_var("HOME");
+
+        assert_eq!(missing_layout, "single-file");
+        assert_eq!(missing_index, "false");
+        assert_eq!(layout, "per-idea");
+        assert_eq!(index, "true");
+        assert!(invalid_index.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_manager__config_read__editor_and_pager() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let config_path = config_dir.join("config.json");
+
+        fs::write(&config_path, "{\"repo\": \"some-repo\"}")?;
+        let missing_editor = cm.config_read(ConfigType::Editor)?;
+
+        fs::write(
+            &config_path,
+            "{\"repo\": \"some-repo\", \"editor\": \"code --wait\", \"pager\": \"less -R\"}",
+        )?;
+        let editor = cm.config_read(ConfigType::Editor)?;
+        let pager = cm.config_read(ConfigType::Pager)?;
+
+        env::remove_var("HOME");
+
+        assert_eq!(missing_editor, "");
+        assert_eq!(editor, "code --wait");
+        assert_eq!(pager, "less -R");
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_manager__config_read__https_token() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let config_path = config_dir.join("config.json");
+
+        fs::write(&config_path, "{\"repo\": \"some-repo\"}")?;
+        let missing_username = cm.config_read(ConfigType::HttpsUsername)?;
+
+        fs::write(
+            &config_path,
+            "{\"repo\": \"some-repo\", \"https_username\": \"some-user\", \"https_token_env\": \"SOME_TOKEN\", \"https_token_command\": \"pass show token\"}",
+        )?;
+        let username = cm.config_read(ConfigType::HttpsUsername)?;
+        let token_env = cm.config_read(ConfigType::HttpsTokenEnv)?;
+        let token_command = cm.config_read(ConfigType::HttpsTokenCommand)?;
+
+        env::remove_var("HOME");
+
+        assert_eq!(missing_username, "");
+        assert_eq!(username, "some-user");
+        assert_eq!(token_env, "SOME_TOKEN");
+        assert_eq!(token_command, "pass show token");
+        Ok(())
+    }
+
     #[test]
     fn test_config_manager__config_read__when__file_does_not_exist__failure() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (_config_dir, _tmp_dir) = set_and_create_config_dir()?;
 
         let actual = cm.config_read(ConfigType::Repo).map_err(|e| e.kind());
@@ -263,7 +514,7 @@ mod tests {
     #[test]
     fn test_config_manager__config_write__config_file_does_not_already_exist__success() -> TestResult
     {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
 
         let write_result = cm.config_write(ConfigType::Repo, String::from("this-specific-value"));
@@ -282,7 +533,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_write__config_file_already_exists__success() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
         // Create file but leave it empty
         let _file = fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
@@ -304,9 +555,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_config_manager__config_write__keeps_existing_values__success() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+        let mut file =
+            fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
+        file.write_all("{\"repo\": \"a-long-repo-value\", \"ssh_key\": \"some-key\"}".as_bytes())?;
+
+        cm.config_write(ConfigType::Repo, String::from("short"))?;
+        cm.config_write(ConfigType::Branch, String::from("ideas"))?;
+
+        env::remove_var("HOME");
+
+        let contents = get_file_contents(&config_dir)?;
+        let expected = "{\"repo\":\"short\",\"ssh_key\":\"some-key\",\"branch\":\"ideas\"}";
+
+        assert_eq!(contents, expected);
+        Ok(())
+    }
+
     #[test]
     fn test_config_manager__config_rm__success() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
         // Create file but leave it empty
         let _file = fs::File::create(path::Path::new(&config_dir.join("config.json").as_os_str()))?;
@@ -321,7 +592,7 @@ mod tests {
 
     #[test]
     fn test_config_manager__config_rm__file_does_not_exist__failure() -> TestResult {
-        let cm = ConfigManager::default();
+        let cm = ConfigManager;
         let (_config_dir, _tmp_dir) = set_and_create_config_dir()?;
 
         let actual = cm.config_rm().map_err(|e| e.kind());
@@ -333,6 +604,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_config_manager__unpushed_read__file_does_not_exist__empty() -> TestResult {
+        let cm = ConfigManager;
+        let (_config_dir, _tmp_dir) = set_and_create_config_dir()?;
+
+        let actual = cm.unpushed_read()?;
+
+        env::remove_var("HOME");
+
+        assert!(actual.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_manager__unpushed_write__success() -> TestResult {
+        let cm = ConfigManager;
+        let (config_dir, _tmp_dir) = set_and_create_config_dir()?;
+
+        cm.unpushed_write(vec![String::from("some-oid"), String::from("other-oid")])?;
+        let actual = cm.unpushed_read()?;
+        let contents = fs::read_to_string(config_dir.join("state.json"))?;
+
+        env::remove_var("HOME");
+
+        assert_eq!(actual, vec!["some-oid", "other-oid"]);
+        assert_eq!(contents, "{\"unpushed\":[\"some-oid\",\"other-oid\"]}");
+        Ok(())
+    }
+
     fn set_config_dir() -> io::Result<(PathBuf, TempDir)> {
         let tmp_dir = TempDir::new()?;
         // Create the config dir. When tmp_dir is destroyed it will be deleted
@@ -0,0 +1,623 @@
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+use std::str::FromStr;
+use std::{fmt, io};
+
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
+
+const HEADING_PREFIX: &str = "## ";
+const CODE_FENCE: &str = "```";
+const METADATA_SEPARATOR: &str = " | ";
+const METADATA_ID: &str = "id";
+const METADATA_CAPTURED: &str = "captured";
+const METADATA_STATUS: &str = "status";
+const METADATA_TAGS: &str = "tags";
+const TAG_PREFIX: char = '#';
+const TRAILER_TAGS: &str = "Tags";
+const TRAILER_IDEA_ID: &str = "Idea-Id";
+const ID_LENGTH: usize = 7;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Idea {
+    // Ideas captured before eureka gave ideas an id have none
+    pub id: Option<String>,
+    pub summary: String,
+    pub body: String,
+    // Ideas written before eureka rendered entries have no timestamp
+    pub captured: Option<DateTime<FixedOffset>>,
+    pub status: Status,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Status {
+    #[default]
+    Open,
+    InProgress,
+    Done,
+    Dropped,
+}
+
+impl Status {
+    pub const OPEN: &'static str = "open";
+    pub const IN_PROGRESS: &'static str = "in-progress";
+    pub const DONE: &'static str = "done";
+    pub const DROPPED: &'static str = "dropped";
+
+    // Done and dropped ideas are hidden unless asked for
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Status::Done | Status::Dropped)
+    }
+}
+
+impl FromStr for Status {
+    type Err = io::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            Status::OPEN => Ok(Status::Open),
+            Status::IN_PROGRESS => Ok(Status::InProgress),
+            Status::DONE => Ok(Status::Done),
+            Status::DROPPED => Ok(Status::Dropped),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unknown status \"{}\"", value),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            Status::Open => Status::OPEN,
+            Status::InProgress => Status::IN_PROGRESS,
+            Status::Done => Status::DONE,
+            Status::Dropped => Status::DROPPED,
+        };
+        write!(f, "{}", value)
+    }
+}
+
+impl Idea {
+    /// `#tag` tokens in the summary are removed from it and added to the tags.
+    /// `## ` headings in the body are demoted to `### `, as they would start a new idea.
+    pub fn new(summary: &str, body: &str, tags: &[String]) -> Self {
+        let (summary, summary_tags) = extract_tags(summary);
+
+        let mut all_tags = Vec::new();
+        for tag in tags.iter().chain(summary_tags.iter()) {
+            add_tag(&mut all_tags, tag);
+        }
+
+        let captured = Local::now().fixed_offset();
+
+        Self {
+            id: new_id(&summary, &captured),
+            summary,
+            body: demote_headings(body.trim()),
+            captured: Some(captured),
+            status: Status::Open,
+            tags: all_tags,
+        }
+    }
+
+    /// The message of the commit that captures the idea: the summary as subject,
+    /// with the id and the tags in `Idea-Id:` and `Tags:` trailers
+    pub fn commit_message(&self) -> String {
+        let mut message = self.change_message(&self.summary);
+        if !self.tags.is_empty() {
+            let separator = if self.id.is_some() { "\n" } else { "\n\n" };
+            message.push_str(&format!(
+                "{}{}: {}",
+                separator,
+                TRAILER_TAGS,
+                self.tags.join(", ")
+            ));
+        }
+        message
+    }
+
+    /// The message of a commit that changes the idea, with its id in an `Idea-Id:` trailer
+    pub fn change_message(&self, subject: &str) -> String {
+        match &self.id {
+            Some(id) => format!("{}\n\n{}: {}", subject, TRAILER_IDEA_ID, id),
+            None => subject.to_string(),
+        }
+    }
+
+    /// Renders the idea as a markdown section:
+    ///
+    /// ```markdown
+    /// ## <summary>
+    ///
+    /// _id: <id> | captured: <timestamp> | status: <status> | tags: <tag>, <tag>_
+    ///
+    /// <body>
+    /// ```
+    pub fn render(&self) -> String {
+        let mut section = format!("{}{}\n", HEADING_PREFIX, self.summary);
+
+        let metadata = self.render_metadata();
+        if !metadata.is_empty() {
+            section.push_str(&format!("\n_{}_\n", metadata));
+        }
+
+        if !self.body.is_empty() {
+            section.push_str(&format!("\n{}\n", self.body));
+        }
+
+        section
+    }
+
+    fn render_metadata(&self) -> String {
+        let mut fields = Vec::new();
+
+        if let Some(id) = &self.id {
+            fields.push(format!("{}: {}", METADATA_ID, id));
+        }
+
+        if let Some(captured) = self.captured {
+            fields.push(format!(
+                "{}: {}",
+                METADATA_CAPTURED,
+                captured.to_rfc3339_opts(SecondsFormat::Secs, false)
+            ));
+        }
+
+        // Ideas are open unless said otherwise
+        if self.status != Status::Open {
+            fields.push(format!("{}: {}", METADATA_STATUS, self.status));
+        }
+
+        if !self.tags.is_empty() {
+            fields.push(format!("{}: {}", METADATA_TAGS, self.tags.join(", ")));
+        }
+
+        fields.join(METADATA_SEPARATOR)
+    }
+
+    // Returns false if the line isn't a metadata line, leaving the idea untouched
+    fn parse_metadata(&mut self, line: &str) -> bool {
+        let metadata = match line
+            .trim()
+            .strip_prefix('_')
+            .and_then(|line| line.strip_suffix('_'))
+        {
+            Some(metadata) => metadata,
+            None => return false,
+        };
+
+        let mut id = None;
+        let mut captured = None;
+        let mut status = Status::Open;
+        let mut tags = Vec::new();
+
+        for field in metadata.split(METADATA_SEPARATOR) {
+            match field.split_once(": ") {
+                Some((METADATA_ID, value)) => id = Some(value.to_string()),
+                Some((METADATA_CAPTURED, value)) => match DateTime::parse_from_rfc3339(value) {
+                    Ok(timestamp) => captured = Some(timestamp),
+                    Err(_) => return false,
+                },
+                Some((METADATA_STATUS, value)) => match value.parse() {
+                    Ok(value) => status = value,
+                    Err(_) => return false,
+                },
+                Some((METADATA_TAGS, value)) => {
+                    tags = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(String::from)
+                        .collect()
+                }
+                _ => return false,
+            }
+        }
+
+        self.id = id;
+        self.captured = captured;
+        self.status = status;
+        self.tags = tags;
+        true
+    }
+}
+
+/// Splits a summary into the summary without its `#tag` tokens and the tags.
+pub fn extract_tags(summary: &str) -> (String, Vec<String>) {
+    let mut words = Vec::new();
+    let mut tags = Vec::new();
+
+    for word in summary.split_whitespace() {
+        match parse_tag(word) {
+            Some(tag) => add_tag(&mut tags, tag),
+            None => words.push(word),
+        }
+    }
+
+    (words.join(" "), tags)
+}
+
+/// Parses the tags of a commit from its `Tags:` trailer, as well as from any
+/// `#tag` tokens in its subject, which is how tags were written before.
+pub fn parse_commit_tags(message: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    if let Some(subject) = message.lines().next() {
+        for tag in extract_tags(subject).1 {
+            add_tag(&mut tags, &tag);
+        }
+    }
+
+    for value in trailer_values(message, TRAILER_TAGS) {
+        for tag in value
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+        {
+            add_tag(&mut tags, tag);
+        }
+    }
+
+    tags
+}
+
+/// Parses the id of the idea a commit captured or changed from its `Idea-Id:` trailer
+pub fn parse_commit_idea_id(message: &str) -> Option<String> {
+    trailer_values(message, TRAILER_IDEA_ID)
+        .next()
+        .map(String::from)
+}
+
+// Values of the trailers with the key, the subject is never a trailer
+fn trailer_values<'m>(message: &'m str, key: &'m str) -> impl Iterator<Item = &'m str> {
+    message.lines().skip(1).filter_map(move |line| {
+        line.strip_prefix(key)
+            .and_then(|line| line.strip_prefix(": "))
+            .map(str::trim)
+    })
+}
+
+// A short hash of the summary and the time it was captured, like a short commit id
+fn new_id(summary: &str, captured: &DateTime<FixedOffset>) -> Option<String> {
+    let seed = format!(
+        "{}\n{}",
+        captured.to_rfc3339_opts(SecondsFormat::Nanos, false),
+        summary
+    );
+    git2::Oid::hash_object(git2::ObjectType::Blob, seed.as_bytes())
+        .ok()
+        .map(|oid| oid.to_string()[..ID_LENGTH].to_string())
+}
+
+// A tag starts with a letter, so headings like `#1` aren't tags
+fn parse_tag(word: &str) -> Option<&str> {
+    let tag = word.strip_prefix(TAG_PREFIX)?;
+    let is_valid = tag.starts_with(|c: char| c.is_alphabetic())
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    is_valid.then_some(tag)
+}
+
+// Tags are compared ignoring case, the first spelling is kept
+fn add_tag(tags: &mut Vec<String>, tag: &str) {
+    let tag = tag.trim().trim_start_matches(TAG_PREFIX);
+    if !tag.is_empty() && !tags.iter().any(|other| other.eq_ignore_ascii_case(tag)) {
+        tags.push(tag.to_string());
+    }
+}
+
+/// Parses every `## ` section of an idea file into an idea. Content before the
+/// first section, such as the title of the file, is not part of any idea.
+pub fn parse_ideas(contents: &str) -> Vec<Idea> {
+    parse_idea_sections(contents)
+        .into_iter()
+        .map(|(idea, _)| idea)
+        .collect()
+}
+
+/// Like `parse_ideas`, but also returns the range of lines of every section
+pub fn parse_idea_sections(contents: &str) -> Vec<(Idea, Range<usize>)> {
+    let mut ideas = Vec::new();
+    let mut current: Option<(Idea, usize, Vec<&str>)> = None;
+    let mut in_code_block = false;
+
+    for (line_number, line) in contents.lines().enumerate() {
+        if line.trim_start().starts_with(CODE_FENCE) {
+            in_code_block = !in_code_block;
+        }
+
+        if !in_code_block {
+            if let Some(summary) = line.strip_prefix(HEADING_PREFIX) {
+                if let Some((idea, start, body)) = current.take() {
+                    ideas.push((finish_idea(idea, body), start..line_number));
+                }
+
+                let idea = Idea {
+                    id: None,
+                    summary: summary.trim().to_string(),
+                    body: String::new(),
+                    captured: None,
+                    status: Status::Open,
+                    tags: Vec::new(),
+                };
+                current = Some((idea, line_number, Vec::new()));
+                continue;
+            }
+        }
+
+        if let Some((_, _, body)) = current.as_mut() {
+            body.push(line);
+        }
+    }
+
+    if let Some((idea, start, body)) = current.take() {
+        ideas.push((finish_idea(idea, body), start..contents.lines().count()));
+    }
+
+    ideas
+}
+
+// Code blocks are left as is, as headings in them don't start a new idea
+fn demote_headings(body: &str) -> String {
+    let mut in_code_block = false;
+
+    body.lines()
+        .map(|line| {
+            if line.trim_start().starts_with(CODE_FENCE) {
+                in_code_block = !in_code_block;
+            }
+
+            if !in_code_block && line.starts_with(HEADING_PREFIX) {
+                format!("#{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn finish_idea(mut idea: Idea, lines: Vec<&str>) -> Idea {
+    let mut lines = lines.into_iter().skip_while(|line| line.trim().is_empty());
+    let mut body = Vec::new();
+
+    // The metadata line is the first line of the section, if there is one
+    if let Some(first) = lines.next() {
+        if !idea.parse_metadata(first) {
+            body.push(first);
+        }
+    }
+    body.extend(lines);
+
+    idea.body = body.join("\n").trim().to_string();
+    idea
+}
+
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod tests {
+    use crate::idea::{
+        extract_tags, parse_commit_idea_id, parse_commit_tags, parse_idea_sections, parse_ideas,
+        Idea, Status,
+    };
+    use chrono::DateTime;
+
+    fn idea() -> Idea {
+        Idea {
+            id: Some(String::from("abc1234")),
+            summary: String::from("some-summary"),
+            body: String::from("some-body\n\n- some-bullet"),
+            captured: Some(DateTime::parse_from_rfc3339("2023-04-01T12:30:00+02:00").unwrap()),
+            status: Status::Open,
+            tags: vec![String::from("product"), String::from("blog")],
+        }
+    }
+
+    #[test]
+    fn test_idea__new() {
+        let actual = Idea::new("  some-summary ", "\nsome-body\n", &[]);
+
+        assert_eq!(actual.id.unwrap().len(), 7);
+        assert_eq!(actual.summary, "some-summary");
+        assert_eq!(actual.body, "some-body");
+        assert!(actual.captured.is_some());
+        assert!(actual.tags.is_empty());
+    }
+
+    #[test]
+    fn test_idea__new__with_tags() {
+        let tags = [String::from("product"), String::from("Blog")];
+        let actual = Idea::new("some #blog summary #tooling", "", &tags);
+
+        assert_eq!(actual.summary, "some summary");
+        assert_eq!(actual.tags, vec!["product", "Blog", "tooling"]);
+    }
+
+    #[test]
+    fn test_idea__new__body_with_heading() {
+        let body = "some-body\n\n## some-heading\n\n```\n## some-code\n```";
+        let idea = Idea::new("some-summary", body, &[]);
+
+        let actual = parse_ideas(&format!("# Ideas\n\n{}", idea.render()));
+
+        assert_eq!(
+            idea.body,
+            "some-body\n\n### some-heading\n\n```\n## some-code\n```"
+        );
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].body, idea.body);
+    }
+
+    #[test]
+    fn test_idea__extract_tags() {
+        assert_eq!(
+            extract_tags("Fix #1 priority #some-tag # #tag_2"),
+            (
+                String::from("Fix #1 priority #"),
+                vec![String::from("some-tag"), String::from("tag_2")]
+            )
+        );
+    }
+
+    #[test]
+    fn test_idea__commit_message() {
+        assert_eq!(
+            idea().commit_message(),
+            "some-summary\n\nIdea-Id: abc1234\nTags: product, blog"
+        );
+
+        let idea = Idea {
+            id: None,
+            tags: Vec::new(),
+            ..idea()
+        };
+        assert_eq!(idea.commit_message(), "some-summary");
+    }
+
+    #[test]
+    fn test_idea__change_message() {
+        assert_eq!(
+            idea().change_message("Done: some-summary"),
+            "Done: some-summary\n\nIdea-Id: abc1234"
+        );
+    }
+
+    #[test]
+    fn test_idea__parse_commit_idea_id() {
+        assert_eq!(
+            parse_commit_idea_id(&idea().commit_message()),
+            Some(String::from("abc1234"))
+        );
+        assert_eq!(parse_commit_idea_id("Idea-Id: in-the-subject"), None);
+    }
+
+    #[test]
+    fn test_idea__parse_commit_tags() {
+        assert_eq!(
+            parse_commit_tags(&idea().commit_message()),
+            vec!["product", "blog"]
+        );
+        assert_eq!(
+            parse_commit_tags("some #blog summary\n\nsome body\n\nTags: product, Blog"),
+            vec!["blog", "product"]
+        );
+        assert!(parse_commit_tags("some-summary").is_empty());
+    }
+
+    #[test]
+    fn test_idea__render() {
+        let actual = idea().render();
+        let expected = "## some-summary
+
+_id: abc1234 | captured: 2023-04-01T12:30:00+02:00 | tags: product, blog_
+
+some-body
+
+- some-bullet
+";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_idea__render__with_status() {
+        let idea = Idea {
+            body: String::new(),
+            status: Status::InProgress,
+            ..idea()
+        };
+
+        assert_eq!(
+            idea.render(),
+            "## some-summary\n\n_id: abc1234 | captured: 2023-04-01T12:30:00+02:00 | status: in-progress | tags: product, blog_\n"
+        );
+    }
+
+    #[test]
+    fn test_idea__status__from_str() {
+        assert_eq!("open".parse::<Status>().unwrap(), Status::Open);
+        assert_eq!("in-progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("done".parse::<Status>().unwrap(), Status::Done);
+        assert_eq!("dropped".parse::<Status>().unwrap(), Status::Dropped);
+        assert!("closed".parse::<Status>().is_err());
+    }
+
+    #[test]
+    fn test_idea__render__without_body_and_metadata() {
+        let idea = Idea {
+            id: None,
+            body: String::new(),
+            captured: None,
+            tags: Vec::new(),
+            ..idea()
+        };
+
+        assert_eq!(idea.render(), "## some-summary\n");
+    }
+
+    #[test]
+    fn test_idea__parse_ideas__rendered_ideas() {
+        let other = Idea {
+            summary: String::from("other-summary"),
+            body: String::new(),
+            tags: Vec::new(),
+            ..idea()
+        };
+        let contents = format!("# Ideas\n\n{}\n{}", idea().render(), other.render());
+
+        let actual = parse_ideas(&contents);
+
+        assert_eq!(actual, vec![idea(), other]);
+    }
+
+    #[test]
+    fn test_idea__parse_ideas__with_status() {
+        let done = Idea {
+            status: Status::Done,
+            ..idea()
+        };
+
+        assert_eq!(parse_ideas(&done.render()), vec![done]);
+    }
+
+    #[test]
+    fn test_idea__parse_idea_sections() {
+        let contents = "# Ideas\n\n## some-summary\n\nsome-body\n\n## other-summary\n";
+
+        let actual: Vec<_> = parse_idea_sections(contents)
+            .into_iter()
+            .map(|(_, lines)| lines)
+            .collect();
+
+        assert_eq!(actual, vec![2..6, 6..7]);
+    }
+
+    #[test]
+    fn test_idea__parse_ideas__free_form_sections() {
+        let contents = "# Ideas
+
+## some-summary
+_emphasized_ body
+
+```sh
+## not a heading
+```
+## other-summary
+";
+
+        let actual = parse_ideas(contents);
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].summary, "some-summary");
+        assert_eq!(
+            actual[0].body,
+            "_emphasized_ body\n\n```sh\n## not a heading\n```"
+        );
+        assert_eq!(actual[0].captured, None);
+        assert_eq!(actual[1].summary, "other-summary");
+        assert_eq!(actual[1].body, "");
+    }
+}
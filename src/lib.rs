@@ -3,24 +3,46 @@ extern crate dirs;
 extern crate log;
 extern crate core;
 
-use std::io;
+use std::collections::HashMap;
 use std::io::{Error, ErrorKind};
+use std::{fs, io};
 
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use ratatui::backend::Backend;
+use ratatui::Terminal;
+
+use crate::browse::{Action, BrowseEvents, Browser};
 use crate::config_manager::{
-    ConfigManagement,
-    ConfigType::{Repo, SshKey},
+    ConfigManagement, ConfigType,
+    ConfigType::{Branch, HttpsTokenCommand, HttpsTokenEnv, ReadmeIndex, Remote, Repo, SshKey},
+    DEFAULT_BRANCH, DEFAULT_REMOTE,
 };
-use crate::git::GitManagement;
+use crate::git::{is_https_url, is_passphrase_needed, GitManagement, LogEntry};
+use crate::idea::{extract_tags, parse_ideas, Idea, Status};
 use crate::printer::{Print, PrintColor};
-use crate::program_access::ProgramOpener;
+use crate::program_access::{ExitStatusError, ProgramOpener};
 use crate::reader::ReadInput;
-use std::path::Path;
+use crate::search::Query;
+use crate::storage::{Layout, StoredIdea, IDEA_FILE_NAME};
+use std::path::{Path, PathBuf};
 
+pub mod browse;
 pub mod config_manager;
 pub mod git;
+pub mod idea;
 pub mod printer;
 pub mod program_access;
 pub mod reader;
+pub mod render;
+pub mod search;
+pub mod storage;
+
+const STARTER_README: &str =
+    "# Ideas\n\nCaptured with [eureka](https://github.com/simeg/eureka).\n";
+const INITIAL_COMMIT_MESSAGE: &str = "Initial commit";
+
+const BODY_HINT: &str =
+    "<!-- Describe your idea above this line. It's fine to leave it empty. This line is removed -->";
 
 pub struct Eureka<
     CM: ConfigManagement,
@@ -36,13 +58,120 @@ pub struct Eureka<
     program_opener: PO,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct EurekaOptions {
     // Clear the stored config
     pub clear_config: bool,
 
     // Open idea document with $PAGER (fall back to `less`)
     pub view: bool,
+
+    // With `view`, also show ideas that are done or dropped
+    pub all: bool,
+
+    // With `view`, print the ideas as rendered markdown instead of opening $PAGER
+    pub render: bool,
+
+    // Set when stdout is a terminal, so rendered ideas are shown a page at a time
+    pub page_height: Option<usize>,
+
+    // Run a subcommand instead of capturing an idea
+    pub command: Option<EurekaCommand>,
+
+    // Capture an idea with this summary without prompting or opening $EDITOR
+    pub summary: Option<String>,
+
+    // Body of the idea captured without prompting
+    pub body: Option<String>,
+
+    // When prompting for an idea, type the body at the prompt instead of opening $EDITOR
+    pub no_editor: bool,
+
+    // Tags of the captured idea, or with `view` only show ideas with any of these tags
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum EurekaCommand {
+    // Push ideas that could not be pushed when they were captured
+    Sync,
+
+    // Check the config, repo, remote and programs eureka uses
+    Doctor,
+
+    // Create a new ideas repo and point the config to it
+    Init {
+        // Absolute path of the repo, created if missing
+        path: PathBuf,
+        // URL of the remote to add, if any
+        remote_url: Option<String>,
+    },
+
+    // Print a table of the captured ideas, newest first
+    List {
+        // Only list ideas captured on or after this date
+        since: Option<NaiveDate>,
+        // List at most this many ideas
+        limit: Option<usize>,
+        // List the oldest ideas first
+        reverse: bool,
+    },
+
+    // Print the idea with this id
+    Show {
+        id: String,
+    },
+
+    // Revise the idea with this id in $EDITOR
+    Edit {
+        id: String,
+    },
+
+    // Revert the last commit, or remove it if it wasn't pushed yet
+    Undo {
+        // Also undo a commit that wasn't made by eureka
+        force: bool,
+    },
+
+    // Change the status of the idea with this id
+    SetStatus {
+        id: String,
+        status: Status,
+    },
+
+    // Print the ideas matching a query, with the matches highlighted
+    Search {
+        query: String,
+        // Match the terms of the query as regular expressions
+        is_regex: bool,
+        is_case_sensitive: bool,
+    },
+}
+
+const SEARCH_SUMMARY_PREFIX: &str = "## ";
+const SEARCH_BODY_PREFIX: &str = "    ";
+
+// A failed check of `eureka doctor` and how to fix it
+struct Problem {
+    description: String,
+    hint: String,
+}
+
+impl Problem {
+    fn new(description: String, hint: impl Into<String>) -> Self {
+        Self {
+            description,
+            hint: hint.into(),
+        }
+    }
+}
+
+// A row of `eureka list`
+struct ListRow {
+    date: Option<DateTime<FixedOffset>>,
+    id: Option<String>,
+    short_id: Option<String>,
+    summary: String,
 }
 
 impl<CM, W, R, G, PO> Eureka<CM, W, R, G, PO>
@@ -73,86 +202,854 @@ where
         }
 
         if opts.view {
-            self.open_idea_file()?;
+            self.open_idea_file(&opts.tags, opts.all, opts.render, opts.page_height)?;
             return Ok(());
         }
 
-        if self.is_config_missing() {
+        // A new repo is configured by `init` itself, and `doctor` reports a missing config
+        let skips_setup = matches!(
+            opts.command,
+            Some(EurekaCommand::Init { .. }) | Some(EurekaCommand::Doctor)
+        );
+        if self.is_config_missing() && !skips_setup {
             debug!("Config is missing");
-
-            // If config dir is missing - create it
-            if !self.cm.config_dir_exists() {
-                self.cm.config_dir_create()?;
-                debug!("Created config dir");
+            self.first_time_setup()
+        } else {
+            match (opts.command, opts.summary) {
+                (Some(EurekaCommand::Sync), _) => self.sync(),
+                (Some(EurekaCommand::Doctor), _) => self.doctor(),
+                (Some(EurekaCommand::Init { path, remote_url }), _) => {
+                    self.init(&path, remote_url.as_deref())
+                }
+                (Some(EurekaCommand::Undo { force }), _) => self.undo(force),
+                (Some(EurekaCommand::Show { id }), _) => self.show(&id),
+                (Some(EurekaCommand::Edit { id }), _) => self.edit(&id),
+                (Some(EurekaCommand::SetStatus { id, status }), _) => self.set_status(&id, status),
+                (
+                    Some(EurekaCommand::List {
+                        since,
+                        limit,
+                        reverse,
+                    }),
+                    _,
+                ) => self.list(since, limit, reverse),
+                (
+                    Some(EurekaCommand::Search {
+                        query,
+                        is_regex,
+                        is_case_sensitive,
+                    }),
+                    _,
+                ) => self.search(&query, is_regex, is_case_sensitive),
+                (None, Some(summary)) => self.capture_idea(summary, opts.body, &opts.tags),
+                (None, None) => self.ask_for_idea(&opts.tags, opts.no_editor),
             }
+        }
+    }
+
+    /// Asks for the repo, branch, remote and ssh key, and writes them to the config
+    pub fn first_time_setup(&mut self) -> io::Result<()> {
+        // If config dir is missing - create it
+        if !self.cm.config_dir_exists() {
+            self.cm.config_dir_create()?;
+            debug!("Created config dir");
+        }
 
-            self.printer.fts_banner()?;
+        self.printer.fts_banner()?;
 
-            // If repo path is missing - ask for it
-            if self.cm.config_read(Repo).is_err() {
-                self.setup_repo_path()?;
-                debug!("Setup repo path successfully");
+        // If repo path is missing - ask for it
+        if self.cm.config_read(Repo).is_err() {
+            let (repo_path, is_new_repo) = self.setup_repo_path()?;
+            debug!("Setup repo path successfully");
+            let branch_name = self.setup_branch()?;
+            debug!("Setup branch successfully");
+            let (remote_name, remote_url) = self.setup_remote(is_new_repo)?;
+            debug!("Setup remote successfully");
+            if is_new_repo {
+                let remote = remote_url.as_deref().map(|url| (remote_name.as_str(), url));
+                self.create_repo(&repo_path, &branch_name, remote)?;
+            }
+            // Ideas that are only committed locally are never pushed, and
+            // only ssh remotes use the ssh key
+            if !remote_name.is_empty() && needs_ssh_key(remote_url.as_deref()) {
                 self.setup_ssh_key()?;
                 debug!("Setup ssh_key path successfully");
             }
-
-            self.printer
-                .println("First time setup complete. Happy ideation!")?;
-            Ok(())
-        } else {
-            self.ask_for_idea()
         }
+
+        self.printer
+            .println("First time setup complete. Happy ideation!")?;
+        Ok(())
     }
 
-    fn ask_for_idea(&mut self) -> io::Result<()> {
+    fn ask_for_idea(&mut self, tags: &[String], no_editor: bool) -> io::Result<()> {
         let mut idea_summary = String::new();
 
-        while idea_summary.is_empty() {
+        // A summary of only tags is empty as well
+        while extract_tags(&idea_summary).0.is_empty() {
             self.printer.input_header(">> Idea summary")?;
             idea_summary = self.reader.read_input()?;
         }
 
+        // The body is asked for first, so a discarded idea leaves the repo untouched
+        let idea_body = if no_editor {
+            self.printer
+                .input_header(">> Idea body (end with an empty line or Ctrl-D)")?;
+            self.reader.read_lines()?
+        } else {
+            match self.ask_for_body() {
+                Ok(Some(idea_body)) => idea_body,
+                Ok(None) => {
+                    return self
+                        .printer
+                        .println("Nothing captured, the editor was closed without changes");
+                }
+                // Quitting the editor with an error, e.g. `:cq` in vim, discards the idea
+                Err(err) if ExitStatusError::is_cause_of(&err) => {
+                    return self.printer.println(&format!("Nothing captured, {}", err));
+                }
+                Err(err) => return Err(err),
+            }
+        };
+
+        let repo_path = self.prepare_repo()?;
+        self.save_idea(&repo_path, Idea::new(&idea_summary, &idea_body, tags))
+    }
+
+    // Opens $EDITOR on a temporary file, so only the body is edited and not the whole idea file.
+    // Returns None if the file was left unchanged.
+    fn ask_for_body(&mut self) -> io::Result<Option<String>> {
+        let body_file = tempfile::Builder::new()
+            .prefix("eureka-")
+            .suffix(".md")
+            .tempfile()?;
+        let template = format!("\n{}\n", BODY_HINT);
+        fs::write(body_file.path(), &template)?;
+
+        self.program_opener
+            .open_editor(&body_file.path().display().to_string())?;
+
+        let contents = fs::read_to_string(body_file.path())?;
+        if contents == template {
+            return Ok(None);
+        }
+        Ok(Some(contents.replace(BODY_HINT, "").trim().to_string()))
+    }
+
+    fn save_idea(&mut self, repo_path: &str, idea: Idea) -> io::Result<()> {
+        let layout = self.layout()?;
+        let readme_index = self.readme_index(layout)?;
+
+        let file_paths = storage::save_idea(Path::new(repo_path), layout, readme_index, &idea)?;
+        self.git_add_commit_push(&file_paths, idea.commit_message(), "your new idea")
+    }
+
+    fn layout(&self) -> io::Result<Layout> {
+        self.cm.config_read(ConfigType::Layout)?.parse()
+    }
+
+    // The index is only kept with the per-idea layout
+    fn readme_index(&self, layout: Layout) -> io::Result<bool> {
+        Ok(layout == Layout::PerIdea && self.cm.config_read(ReadmeIndex)? == "true")
+    }
+
+    fn set_status(&mut self, id: &str, status: Status) -> io::Result<()> {
+        let repo_path = self.prepare_repo()?;
+        let layout = self.layout()?;
+
+        let stored = self.find_idea(&repo_path, layout, id)?;
+        if stored.idea.status == status {
+            return self.printer.println(&format!(
+                "\"{}\" is already {}",
+                stored.idea.summary, status
+            ));
+        }
+
+        let idea = Idea {
+            status,
+            ..stored.idea.clone()
+        };
+        let readme_index = self.readme_index(layout)?;
+        let file_paths =
+            storage::replace_idea(Path::new(&repo_path), layout, readme_index, &stored, &idea)?;
+
+        let action = match status {
+            Status::Open => "Reopen",
+            Status::InProgress => "Start",
+            Status::Done => "Done",
+            Status::Dropped => "Drop",
+        };
+        self.git_add_commit_push(
+            &file_paths,
+            idea.change_message(&format!("{}: {}", action, idea.summary)),
+            "the new status of your idea",
+        )
+    }
+
+    // Opens $EDITOR on a temporary file with only the idea, so the rest of the ideas are left as is
+    fn edit(&mut self, id: &str) -> io::Result<()> {
+        let repo_path = self.prepare_repo()?;
+        let layout = self.layout()?;
+
+        let stored = self.find_idea(&repo_path, layout, id)?;
+        let idea_file = tempfile::Builder::new()
+            .prefix("eureka-")
+            .suffix(".md")
+            .tempfile()?;
+        fs::write(idea_file.path(), stored.idea.render())?;
+
+        match self
+            .program_opener
+            .open_editor(&idea_file.path().display().to_string())
+        {
+            Err(err) if ExitStatusError::is_cause_of(&err) => {
+                return self.printer.println(&format!("Nothing changed, {}", err));
+            }
+            result => result?,
+        }
+
+        let mut edited = parse_ideas(&fs::read_to_string(idea_file.path())?);
+        if edited.len() != 1 || edited[0].summary.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The edited idea must be a single section with a summary, e.g. \"## summary\"",
+            ));
+        }
+        // The id is kept even if it was removed from the metadata line, so the idea can still be found
+        let idea = Idea {
+            id: stored.idea.id.clone(),
+            ..edited.remove(0)
+        };
+
+        if idea == stored.idea {
+            return self.printer.println(&format!(
+                "\"{}\" is unchanged, nothing to commit",
+                idea.summary
+            ));
+        }
+
+        let readme_index = self.readme_index(layout)?;
+        let file_paths =
+            storage::replace_idea(Path::new(&repo_path), layout, readme_index, &stored, &idea)?;
+        self.git_add_commit_push(
+            &file_paths,
+            idea.change_message(&format!("Edit: {}", idea.summary)),
+            "your edited idea",
+        )
+    }
+
+    // Commits made by eureka are recognized by their `Idea-Id:` trailer
+    fn undo(&mut self, force: bool) -> io::Result<()> {
+        self.prepare_repo()?;
+
+        let branch_name = self.cm.config_read(Branch)?;
+        let last = self
+            .git
+            .log(&branch_name)
+            .map_err(io::Error::other)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "There is no commit to undo"))?;
+        if last.idea_id.is_none() && !force {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The last commit \"{}\" wasn't made by eureka, use --force to undo it anyway",
+                    last.subject
+                ),
+            ));
+        }
+
+        let remote_name = self.cm.config_read(Remote)?;
+        let is_pushed = !remote_name.is_empty()
+            && self
+                .git
+                .is_pushed(&remote_name, &branch_name, &last.id)
+                .map_err(io::Error::other)?;
+
+        if !is_pushed {
+            // Nobody else has seen the commit, so it can be removed without a trace
+            self.git.reset(&last.id).map_err(io::Error::other)?;
+            let mut unpushed = self.cm.unpushed_read()?;
+            if unpushed.contains(&last.id) {
+                unpushed.retain(|id| id != &last.id);
+                self.cm.unpushed_write(unpushed)?;
+            }
+            return self.printer.println(&format!(
+                "Removed \"{}\" ({}), it was never pushed",
+                last.subject, last.short_id
+            ));
+        }
+
+        self.printer.println(&format!(
+            "Reverting \"{}\" ({}) on {}..",
+            last.subject, last.short_id, branch_name
+        ))?;
+        let oid = self.git.revert(&last.id).map_err(io::Error::other)?;
+        self.printer.println("Reverted!")?;
+        self.push_change(oid, "the revert")
+    }
+
+    // Reads the local clone only, like `list`
+    fn show(&mut self, id: &str) -> io::Result<()> {
+        let repo_path = self.cm.config_read(Repo)?;
+        self.git
+            .init(&repo_path)
+            .map_err(|git_err| Error::new(ErrorKind::InvalidInput, git_err))?;
+
+        let stored = self.find_idea(&repo_path, self.layout()?, id)?;
+        self.printer.print(&stored.idea.render())
+    }
+
+    /// Runs `eureka browse` until it's quit. Ideas are edited and marked done like
+    /// with `eureka edit` and `eureka done`, with the terminal handed back meanwhile
+    pub fn browse<B: Backend, E: BrowseEvents>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        events: &mut E,
+    ) -> io::Result<()> {
+        let repo_path = self.cm.config_read(Repo)?;
+        self.git
+            .init(&repo_path)
+            .map_err(|git_err| Error::new(ErrorKind::InvalidInput, git_err))?;
+        let layout = self.layout()?;
+
+        let mut browser = Browser::new(self.browsed_ideas(&repo_path, layout)?);
+        loop {
+            terminal.draw(|frame| browser.draw(frame))?;
+
+            let (id, result) = match browser.handle_key(events.next_key()?) {
+                Action::None => continue,
+                Action::Quit => return Ok(()),
+                Action::Copy(text) => {
+                    events.copy(&text)?;
+                    browser.set_status("Copied the idea");
+                    continue;
+                }
+                Action::Edit(id) => {
+                    events.suspend()?;
+                    let result = self.edit(&id);
+                    events.resume()?;
+                    (id, result.map(|_| "Edited"))
+                }
+                Action::Done(id) => {
+                    events.suspend()?;
+                    let result = self.set_status(&id, Status::Done);
+                    events.resume()?;
+                    (id, result.map(|_| "Marked as done"))
+                }
+            };
+
+            // The editor drew over the screen
+            terminal.clear()?;
+            match result {
+                Ok(outcome) => browser.set_status(&format!("{} {}", outcome, id)),
+                Err(err) => browser.set_status(&err.to_string()),
+            }
+            browser.set_ideas(self.browsed_ideas(&repo_path, layout)?);
+        }
+    }
+
+    fn browsed_ideas(
+        &mut self,
+        repo_path: &str,
+        layout: Layout,
+    ) -> io::Result<Vec<(Option<String>, Idea)>> {
+        Ok(self
+            .identified_ideas(repo_path, layout)?
+            .into_iter()
+            .map(|(idea_id, stored)| (idea_id, stored.idea))
+            .collect())
+    }
+
+    // Finds the idea whose id starts with `id`
+    fn find_idea(&mut self, repo_path: &str, layout: Layout, id: &str) -> io::Result<StoredIdea> {
+        let mut found: Vec<(StoredIdea, String)> = self
+            .identified_ideas(repo_path, layout)?
+            .into_iter()
+            .filter_map(|(idea_id, stored)| idea_id.map(|idea_id| (stored, idea_id)))
+            .filter(|(_, idea_id)| idea_id.starts_with(id))
+            .collect();
+
+        match found.len() {
+            0 => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("No idea found with id {}", id),
+            )),
+            1 => Ok(found.remove(0).0),
+            _ => {
+                let matches: Vec<String> = found
+                    .iter()
+                    .map(|(stored, idea_id)| format!("{} {}", idea_id, stored.idea.summary))
+                    .collect();
+                Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Id {} matches several ideas: {}", id, matches.join(", ")),
+                ))
+            }
+        }
+    }
+
+    // Pairs every idea, newest first, with its id. Ideas captured before ideas had
+    // an id are identified by the id of the commit that captured them instead
+    fn identified_ideas(
+        &mut self,
+        repo_path: &str,
+        layout: Layout,
+    ) -> io::Result<Vec<(Option<String>, StoredIdea)>> {
+        let branch_name = self.cm.config_read(Branch)?;
+        let commits = self.git.log(&branch_name).map_err(io::Error::other)?;
+        let ideas = storage::read_stored_ideas(Path::new(repo_path), layout)?;
+
+        Ok(pair_with_commits(ideas, commits, |stored| &stored.idea)
+            .into_iter()
+            .map(|(stored, commit)| {
+                let idea_id = stored
+                    .idea
+                    .id
+                    .clone()
+                    .or_else(|| commit.map(|commit| commit.id));
+                (idea_id, stored)
+            })
+            .collect())
+    }
+
+    fn capture_idea(
+        &mut self,
+        summary: String,
+        body: Option<String>,
+        tags: &[String],
+    ) -> io::Result<()> {
+        let idea = Idea::new(&summary, &body.unwrap_or_default(), tags);
+        if idea.summary.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Idea summary can't be empty",
+            ));
+        }
+
+        let repo_path = self.prepare_repo()?;
+
+        self.save_idea(&repo_path, idea)
+    }
+
+    // Gets the repo up to date on the configured branch and returns its path
+    fn prepare_repo(&mut self) -> io::Result<String> {
         let repo_path = self.cm.config_read(Repo)?;
         // We can set initialize git now as we have the repo path
         self.git
             .init(&repo_path)
             .map_err(|git_err| Error::new(ErrorKind::InvalidInput, git_err))?;
 
-        self.program_opener
-            .open_editor(&format!("{}/README.md", &repo_path))
-            .and(self.git_add_commit_push(idea_summary))
+        let branch_name = self.cm.config_read(Branch)?;
+        self.git
+            .checkout_branch(&branch_name)
+            .map_err(io::Error::other)?;
+
+        let remote_name = self.cm.config_read(Remote)?;
+        if !remote_name.is_empty() {
+            self.update_from_remote(&remote_name, &branch_name)?;
+        }
+
+        Ok(repo_path)
+    }
+
+    // Catch up with ideas captured on other machines, so the new idea can be pushed
+    fn update_from_remote(&mut self, remote_name: &str, branch_name: &str) -> io::Result<()> {
+        self.printer.println(&format!(
+            "Fetching latest ideas from {}/{}..",
+            remote_name, branch_name
+        ))?;
+
+        if let Err(git_err) = self.with_passphrase(|git| git.fetch(remote_name, branch_name)) {
+            // Being offline should not stop anyone from capturing an idea
+            return self
+                .printer
+                .error(&format!("Could not fetch: {}", git_err.message()));
+        }
+
+        let rebased = self
+            .git
+            .rebase(remote_name, branch_name)
+            .map_err(io::Error::other)?;
+        if rebased.is_empty() {
+            return Ok(());
+        }
+
+        // The queued commits got new ids, or are gone if they were upstream already
+        let unpushed = self
+            .cm
+            .unpushed_read()?
+            .into_iter()
+            .filter_map(
+                |id| match rebased.iter().find(|(old_id, _)| old_id == &id) {
+                    Some((_, new_id)) => new_id.clone(),
+                    None => Some(id),
+                },
+            )
+            .collect();
+        self.cm.unpushed_write(unpushed)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        let unpushed = self.cm.unpushed_read()?;
+        if unpushed.is_empty() {
+            self.printer.println("No ideas waiting to push")?;
+            return Ok(());
+        }
+
+        let remote_name = self.cm.config_read(Remote)?;
+        if remote_name.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No remote configured, nothing can be pushed",
+            ));
+        }
+
+        let repo_path = self.cm.config_read(Repo)?;
+        self.git
+            .init(&repo_path)
+            .map_err(|git_err| Error::new(ErrorKind::InvalidInput, git_err))?;
+
+        let branch_name = self.cm.config_read(Branch)?;
+        self.printer.println(&format!(
+            "Pushing {} idea(s) to {}/{}..",
+            unpushed.len(),
+            &remote_name,
+            &branch_name
+        ))?;
+        self.push_or_merge(&remote_name, &branch_name)
+            .map_err(io::Error::other)?;
+        self.cm.unpushed_write(Vec::new())?;
+        self.printer.println("Pushed!")
+    }
+
+    fn list(
+        &mut self,
+        since: Option<NaiveDate>,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> io::Result<()> {
+        let repo_path = self.cm.config_read(Repo)?;
+        self.git
+            .init(&repo_path)
+            .map_err(|git_err| Error::new(ErrorKind::InvalidInput, git_err))?;
+
+        let branch_name = self.cm.config_read(Branch)?;
+        let commits = self.git.log(&branch_name).map_err(io::Error::other)?;
+        let ideas = storage::read_ideas(Path::new(&repo_path), self.layout()?)?;
+
+        let mut rows: Vec<ListRow> = list_rows(ideas, commits)
+            .into_iter()
+            .filter(|row| match since {
+                Some(since) => row.date.is_some_and(|date| date.date_naive() >= since),
+                None => true,
+            })
+            .collect();
+        // Like `git log`, the limit picks the newest ideas before they are reversed
+        if let Some(limit) = limit {
+            rows.truncate(limit);
+        }
+        if reverse {
+            rows.reverse();
+        }
+
+        if rows.is_empty() {
+            return self.printer.println("No ideas found");
+        }
+
+        let id_width = column_width(rows.iter().map(|row| row.id.as_deref()), "ID");
+        let commit_width = column_width(rows.iter().map(|row| row.short_id.as_deref()), "COMMIT");
+
+        self.printer.println(&format!(
+            "{:<10}  {:<id_width$}  {:<commit_width$}  SUMMARY",
+            "DATE",
+            "ID",
+            "COMMIT",
+            id_width = id_width,
+            commit_width = commit_width
+        ))?;
+        for row in rows {
+            let date = row
+                .date
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| String::from("-"));
+            self.printer.println(&format!(
+                "{:<10}  {:<id_width$}  {:<commit_width$}  {}",
+                date,
+                row.id.as_deref().unwrap_or("-"),
+                row.short_id.as_deref().unwrap_or("-"),
+                row.summary,
+                id_width = id_width,
+                commit_width = commit_width
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    // Only searches the local clone, so it works offline
+    fn search(&mut self, query: &str, is_regex: bool, is_case_sensitive: bool) -> io::Result<()> {
+        let query = Query::parse(query, is_regex, is_case_sensitive)?;
+
+        let repo_path = self.cm.config_read(Repo)?;
+        let ideas = storage::read_ideas(Path::new(&repo_path), self.layout()?)?;
+
+        let mut found_count = 0;
+        for idea in ideas.iter().filter(|idea| query.is_match(idea)) {
+            if found_count > 0 {
+                self.printer.println("")?;
+            }
+            found_count += 1;
+
+            self.print_matches(SEARCH_SUMMARY_PREFIX, &idea.summary, &query)?;
+            for line in idea.body.lines() {
+                if !query.find_matches(line).is_empty() {
+                    self.print_matches(SEARCH_BODY_PREFIX, line, &query)?;
+                }
+            }
+        }
+
+        if found_count == 0 {
+            self.printer.println("No ideas found")
+        } else {
+            self.printer.println("")?;
+            self.printer
+                .println(&format!("{} idea(s) found", found_count))
+        }
+    }
+
+    fn print_matches(&mut self, prefix: &str, line: &str, query: &Query) -> io::Result<()> {
+        let matches: Vec<_> = query
+            .find_matches(line)
+            .into_iter()
+            .map(|range| range.start + prefix.len()..range.end + prefix.len())
+            .collect();
+        self.printer
+            .highlight(&format!("{}{}", prefix, line), &matches)
     }
 
     fn clear_config(&self) -> io::Result<()> {
         self.cm.config_rm()
     }
 
-    fn open_idea_file(&self) -> io::Result<()> {
-        self.program_opener
-            .open_pager(&format!("{}/README.md", self.cm.config_read(Repo)?))
+    fn open_idea_file(
+        &mut self,
+        tags: &[String],
+        all: bool,
+        render: bool,
+        page_height: Option<usize>,
+    ) -> io::Result<()> {
+        let repo_path = self.cm.config_read(Repo)?;
+        let layout = self.layout()?;
+
+        let mut ideas = storage::read_ideas(Path::new(&repo_path), layout)?;
+        let has_hidden_ideas = !all && ideas.iter().any(|idea| idea.status.is_closed());
+
+        // The idea file can be paged through as is when every idea is shown
+        if layout == Layout::SingleFile && tags.is_empty() && !has_hidden_ideas {
+            let idea_file_path = format!("{}/{}", repo_path, IDEA_FILE_NAME);
+            if !render && self.open_pager_if_found(&idea_file_path)? {
+                return Ok(());
+            }
+            let contents = fs::read_to_string(&idea_file_path)?;
+            return self.print_rendered(&contents, page_height);
+        }
+
+        if !all {
+            ideas.retain(|idea| !idea.status.is_closed());
+        }
+        if !tags.is_empty() {
+            ideas = self.ideas_with_tags(&repo_path, ideas, tags)?;
+        }
+
+        let rendered = ideas
+            .iter()
+            .map(Idea::render)
+            .collect::<Vec<String>>()
+            .join("\n");
+        if !render {
+            // Gather the ideas into a single file, so they can be paged through at once
+            let ideas_file = tempfile::Builder::new()
+                .prefix("eureka-")
+                .suffix(".md")
+                .tempfile()?;
+            fs::write(ideas_file.path(), &rendered)?;
+
+            if self.open_pager_if_found(&ideas_file.path().display().to_string())? {
+                return Ok(());
+            }
+        }
+        self.print_rendered(&rendered, page_height)
+    }
+
+    // Returns false if there is no pager to open, so the ideas are rendered instead
+    fn open_pager_if_found(&mut self, file_path: &str) -> io::Result<bool> {
+        match self.program_opener.open_pager(file_path) {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                debug!("No pager found: {}", err);
+                Ok(false)
+            }
+            result => result.map(|_| true),
+        }
+    }
+
+    fn print_rendered(&mut self, markdown: &str, page_height: Option<usize>) -> io::Result<()> {
+        // Leave room for the two lines of the prompt
+        let page_length = page_height.map(|height| height.saturating_sub(2).max(1));
+
+        for (line_number, line) in render::render(markdown).iter().enumerate() {
+            if let Some(page_length) = page_length {
+                if line_number > 0 && line_number % page_length == 0 {
+                    self.printer
+                        .input_header("-- More -- Press Enter to continue or q to quit")?;
+                    if self.reader.read_input()? == "q" {
+                        return Ok(());
+                    }
+                }
+            }
+            self.printer.styled(line)?;
+        }
+
+        Ok(())
     }
 
-    fn git_add_commit_push(&mut self, commit_subject: String) -> io::Result<()> {
-        let branch_name = "main";
+    // Keeps the ideas with any of the tags, in their entry, in their summary or in
+    // the commit that captured them
+    fn ideas_with_tags(
+        &mut self,
+        repo_path: &str,
+        ideas: Vec<Idea>,
+        tags: &[String],
+    ) -> io::Result<Vec<Idea>> {
+        self.git
+            .init(repo_path)
+            .map_err(|git_err| Error::new(ErrorKind::InvalidInput, git_err))?;
+        let branch_name = self.cm.config_read(Branch)?;
+        let commits = self.git.log(&branch_name).map_err(io::Error::other)?;
+
+        let mut ideas: Vec<Idea> = pair_with_commits(ideas, commits, |idea| idea)
+            .into_iter()
+            .filter(|(idea, commit)| {
+                let summary_tags = extract_tags(&idea.summary).1;
+                let commit_tags = commit.iter().flat_map(|commit| &commit.tags);
+                idea.tags
+                    .iter()
+                    .chain(&summary_tags)
+                    .chain(commit_tags)
+                    .any(|tag| tags.iter().any(|other| tag.eq_ignore_ascii_case(other)))
+            })
+            .map(|(idea, _)| idea)
+            .collect();
+        // Back to the order they were captured in
+        ideas.reverse();
+        Ok(ideas)
+    }
+
+    fn git_add_commit_push(
+        &mut self,
+        file_paths: &[PathBuf],
+        commit_message: String,
+        change: &str,
+    ) -> io::Result<()> {
+        let branch_name = self.cm.config_read(Branch)?;
         self.printer.println(&format!(
-            "Adding and committing your new idea to {}..",
-            &branch_name
+            "Adding and committing {} to {}..",
+            change, &branch_name
         ))?;
-        self.git
-            .checkout_branch(branch_name)
-            .and_then(|_| self.git.add())
-            .and_then(|_| self.git.commit(commit_subject.as_str()))
+        let oid = self
+            .git
+            .add(file_paths)
+            .and_then(|_| self.git.commit(commit_message.as_str()))
             .map_err(io::Error::other)?;
         self.printer.println("Added and committed!")?;
 
-        self.printer.println("Pushing your new idea..")?;
-        self.git.push(branch_name).map_err(io::Error::other)?;
-        self.printer.println("Pushed!")?;
+        self.push_change(oid, change)
+    }
+
+    // Pushes the committed change, or queues it for `eureka sync` if that fails
+    fn push_change(&mut self, oid: git2::Oid, change: &str) -> io::Result<()> {
+        let branch_name = self.cm.config_read(Branch)?;
+        let remote_name = self.cm.config_read(Remote)?;
+        if remote_name.is_empty() {
+            self.printer
+                .println(&format!("No remote configured, {} is kept locally", change))?;
+            return Ok(());
+        }
+
+        self.printer.println(&format!(
+            "Pushing {} to {}/{}..",
+            change, &remote_name, &branch_name
+        ))?;
+        let mut unpushed = self.cm.unpushed_read()?;
+        match self.push_or_merge(&remote_name, &branch_name) {
+            Ok(()) => {
+                // Pushing the branch also pushed any previously queued ideas
+                if !unpushed.is_empty() {
+                    self.cm.unpushed_write(Vec::new())?;
+                }
+                self.printer.println("Pushed!")?;
+            }
+            Err(git_err) => {
+                // The idea is already committed, so keep it for `eureka sync`
+                unpushed.push(oid.to_string());
+                let unpushed_count = unpushed.len();
+                self.cm.unpushed_write(unpushed)?;
+                self.printer
+                    .error(&format!("Could not push: {}", git_err.message()))?;
+                self.printer.println(&format!(
+                    "Queued, {} idea(s) waiting to push. Run `eureka sync` to push them",
+                    unpushed_count
+                ))?;
+            }
+        }
 
         Ok(())
     }
 
-    fn setup_repo_path(&mut self) -> io::Result<()> {
+    fn push_or_merge(&mut self, remote_name: &str, branch_name: &str) -> Result<(), git2::Error> {
+        match self.with_passphrase(|git| git.push(remote_name, branch_name)) {
+            // Someone else pushed ideas since we fetched, merge them and try again
+            Err(git_err) if git_err.code() == git2::ErrorCode::NotFastForward => {
+                debug!("Push was rejected: {}", git_err);
+                self.with_passphrase(|git| git.fetch(remote_name, branch_name))?;
+                self.git.merge(remote_name, branch_name)?;
+                self.with_passphrase(|git| git.push(remote_name, branch_name))
+            }
+            result => result,
+        }
+    }
+
+    // Runs a git command that talks to the remote. When the ssh key needs a
+    // passphrase it's asked for once, and the command is run again with it.
+    fn with_passphrase<T>(
+        &mut self,
+        command: impl Fn(&G) -> Result<T, git2::Error>,
+    ) -> Result<T, git2::Error> {
+        match command(&self.git) {
+            Err(git_err) if is_passphrase_needed(&git_err) => {
+                let passphrase = self
+                    .ask_for_passphrase()
+                    .map_err(|err| git2::Error::from_str(&err.to_string()))?;
+                self.git.set_ssh_passphrase(&passphrase);
+                command(&self.git)
+            }
+            result => result,
+        }
+    }
+
+    fn ask_for_passphrase(&mut self) -> io::Result<String> {
+        let ssh_key = self.cm.config_read(SshKey)?;
+        self.printer
+            .input_header(&format!("Passphrase for {}", ssh_key))?;
+        let passphrase = self.reader.read_secret()?;
+        // What's typed isn't echoed, Enter included
+        self.printer.println("")?;
+        Ok(passphrase)
+    }
+
+    // Returns the path and whether a new repo should be created there
+    fn setup_repo_path(&mut self) -> io::Result<(PathBuf, bool)> {
         loop {
             self.printer
                 .input_header("Absolute path to your idea repo")?;
@@ -164,37 +1061,544 @@ where
 
             let path = Path::new(user_input);
 
-            if path.is_absolute() {
-                self.cm.config_write(Repo, path.display().to_string())?;
-                break;
-            } else {
+            if !path.is_absolute() {
                 self.printer.error("Path must be absolute")?;
+                continue;
             }
+
+            if self.git.init(user_input).is_err() {
+                if self.confirm(&format!(
+                    "There is no git repo at {}, create a new ideas repo there?",
+                    path.display()
+                ))? {
+                    self.cm.config_write(Repo, path.display().to_string())?;
+                    return Ok((path.to_path_buf(), true));
+                }
+                self.printer
+                    .error(&format!("{} is not a git repo", path.display()))?;
+                continue;
+            }
+
+            let readme_path = path.join(IDEA_FILE_NAME);
+            if !readme_path.exists() {
+                if !self.confirm(&format!(
+                    "There is no {} in {}, create it?",
+                    IDEA_FILE_NAME,
+                    path.display()
+                ))? {
+                    self.printer.error(&format!(
+                        "Ideas are stored in {}, pick a repo that has one",
+                        IDEA_FILE_NAME
+                    ))?;
+                    continue;
+                }
+                // Committed together with the first idea
+                fs::write(&readme_path, STARTER_README)?;
+            }
+
+            self.cm.config_write(Repo, path.display().to_string())?;
+            return Ok((path.to_path_buf(), false));
+        }
+    }
+
+    fn setup_branch(&mut self) -> io::Result<String> {
+        loop {
+            self.printer
+                .input_header(&format!("Name of branch (default: {})", DEFAULT_BRANCH))?;
+            let user_input = self.reader.read_input()?;
+
+            let branch_name = if user_input.is_empty() {
+                DEFAULT_BRANCH.to_string()
+            } else {
+                user_input
+            };
+
+            if git2::Branch::name_is_valid(&branch_name).unwrap_or(false) {
+                self.cm.config_write(Branch, branch_name.clone())?;
+                return Ok(branch_name);
+            } else {
+                self.printer.error("Invalid branch name")?;
+            }
+        }
+    }
+
+    // Returns the name of the remote and its URL, if it's known
+    fn setup_remote(&mut self, is_new_repo: bool) -> io::Result<(String, Option<String>)> {
+        loop {
+            self.printer.input_header(&format!(
+                "Name of remote (default: {}, \"none\" to only commit locally)",
+                DEFAULT_REMOTE
+            ))?;
+            let user_input = self.reader.read_input()?;
+
+            let remote_name = match user_input.as_str() {
+                "" => DEFAULT_REMOTE.to_string(),
+                "none" => String::new(),
+                _ => user_input,
+            };
+
+            let remote_url = if remote_name.is_empty() {
+                None
+            } else if is_new_repo {
+                self.printer.input_header(&format!(
+                    "URL of remote {} (empty to add it later)",
+                    remote_name
+                ))?;
+                Some(self.reader.read_input()?).filter(|url| !url.is_empty())
+            } else {
+                match self.git.remote_url(&remote_name) {
+                    Ok(url) => Some(url),
+                    Err(_) => {
+                        self.printer.error(&format!(
+                            "The repo has no remote named {}, add it with `git remote add` or pick another one",
+                            remote_name
+                        ))?;
+                        continue;
+                    }
+                }
+            };
+
+            self.cm.config_write(Remote, remote_name.clone())?;
+            return Ok((remote_name, remote_url));
         }
-        Ok(())
     }
+
     fn setup_ssh_key(&mut self) -> io::Result<()> {
         loop {
-            self.printer.input_header("Absolute path to your ssh key")?;
+            self.printer
+                .input_header("Absolute path to your ssh key (empty to only use ssh-agent)")?;
             let user_input = &self.reader.read_input()?;
 
+            // The keys in ssh-agent are tried without a configured key as well
             if user_input.is_empty() {
-                continue;
+                return Ok(());
             }
 
-            let path = Path::new(user_input);
-
-            if path.is_absolute() {
-                self.cm.config_write(SshKey, path.display().to_string())?;
-                break;
-            } else {
-                self.printer.error("ssh key path must be absolute")?;
+            match check_ssh_key(Path::new(user_input)) {
+                Ok(()) => {
+                    self.cm.config_write(SshKey, user_input.to_string())?;
+                    return Ok(());
+                }
+                Err(problem) => self.printer.error(&problem)?,
             }
         }
+    }
+
+    // Anything but an answer starting with "n" is a yes
+    fn confirm(&mut self, question: &str) -> io::Result<bool> {
+        self.printer.input_header(&format!("{} (Y/n)", question))?;
+        Ok(!self.reader.read_input()?.to_lowercase().starts_with('n'))
+    }
+
+    fn init(&mut self, repo_path: &Path, remote_url: Option<&str>) -> io::Result<()> {
+        if !self.cm.config_dir_exists() {
+            self.cm.config_dir_create()?;
+            debug!("Created config dir");
+        }
+
+        let branch_name = self
+            .cm
+            .config_read(Branch)
+            .unwrap_or_else(|_| DEFAULT_BRANCH.to_string());
+        // Without a URL there is nothing to push to
+        let remote_name = match remote_url {
+            Some(_) => self
+                .cm
+                .config_read(Remote)
+                .ok()
+                .filter(|remote_name| !remote_name.is_empty())
+                .unwrap_or_else(|| DEFAULT_REMOTE.to_string()),
+            None => String::new(),
+        };
+
+        self.create_repo(
+            repo_path,
+            &branch_name,
+            remote_url.map(|url| (remote_name.as_str(), url)),
+        )?;
+
+        self.cm
+            .config_write(Repo, repo_path.display().to_string())?;
+        self.cm.config_write(Branch, branch_name)?;
+        self.cm.config_write(Remote, remote_name.clone())?;
+        let has_ssh_key = self
+            .cm
+            .config_read(SshKey)
+            .is_ok_and(|ssh_key| !ssh_key.is_empty());
+        if !remote_name.is_empty() && !has_ssh_key && needs_ssh_key(remote_url) {
+            self.setup_ssh_key()?;
+        }
+
+        self.printer.println("Happy ideation!")
+    }
+
+    // Creates the repo with a starter README.md as its first commit, as the
+    // branch can't be checked out before it has a commit
+    fn create_repo(
+        &mut self,
+        repo_path: &Path,
+        branch_name: &str,
+        remote: Option<(&str, &str)>,
+    ) -> io::Result<()> {
+        self.git
+            .create(&repo_path.display().to_string(), branch_name)
+            .map_err(|git_err| match git_err.code() {
+                git2::ErrorCode::Exists => Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("There already is a git repo at {}", repo_path.display()),
+                ),
+                _ => io::Error::other(git_err),
+            })?;
+
+        let readme_path = repo_path.join(IDEA_FILE_NAME);
+        if !readme_path.exists() {
+            fs::write(&readme_path, STARTER_README)?;
+        }
+        self.git
+            .add(&[PathBuf::from(IDEA_FILE_NAME)])
+            .map_err(io::Error::other)?;
+        self.git
+            .commit(INITIAL_COMMIT_MESSAGE)
+            .map_err(io::Error::other)?;
+        self.printer.println(&format!(
+            "Created an ideas repo at {} on branch {}",
+            repo_path.display(),
+            branch_name
+        ))?;
+
+        if let Some((remote_name, url)) = remote {
+            self.git
+                .add_remote(remote_name, url)
+                .map_err(io::Error::other)?;
+            self.printer
+                .println(&format!("Added remote {} {}", remote_name, url))?;
+        }
+
         Ok(())
     }
 
-    fn is_config_missing(&self) -> bool {
+    // Checks the setup step by step and prints what's wrong and how to fix it.
+    // Checks that need something that is broken are skipped.
+    fn doctor(&mut self) -> io::Result<()> {
+        let mut problems = 0;
+
+        let config_path = self.cm.config_path()?;
+        let config = match self.cm.config_read(Repo) {
+            Ok(_) => Ok(format!("Config: {}", config_path.display())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Err(Problem::new(
+                format!("Config: there is no config at {}", config_path.display()),
+                "Run `eureka` to go through first time setup, or `eureka init` to create a new ideas repo",
+            )),
+            Err(err) => Err(Problem::new(
+                format!("Config: {} is not valid: {}", config_path.display(), err),
+                "Fix the JSON, or remove it with `eureka --clear-config` and run first time setup again",
+            )),
+        };
+        let has_repo = self.report(&mut problems, config)? && self.doctor_repo(&mut problems)?;
+
+        let editor = self
+            .program_opener
+            .editor_command()
+            .map(|command| format!("Editor: {}", command))
+            .map_err(|err| {
+                Problem::new(
+                    format!("Editor: {}", err),
+                    "Set `editor` in the config, $VISUAL or $EDITOR to an installed editor, e.g. \"code --wait\"",
+                )
+            });
+        self.report(&mut problems, editor)?;
+
+        let pager = self
+            .program_opener
+            .pager_command()
+            .map(|command| format!("Pager: {}", command))
+            .map_err(|err| {
+                Problem::new(
+                    format!("Pager: {}", err),
+                    "Set `pager` in the config or $PAGER to an installed pager",
+                )
+            });
+        self.report(&mut problems, pager)?;
+
+        // Repo config can override the global identity
+        if has_repo {
+            let identity = self
+                .git
+                .signature()
+                .map(|signature| format!("Git identity: {}", signature))
+                .map_err(|git_err| {
+                    Problem::new(
+                        format!("Git identity: {}", git_err.message()),
+                        "Set it with `git config --global user.name \"Your Name\"` and `git config --global user.email you@example.com`",
+                    )
+                });
+            self.report(&mut problems, identity)?;
+        }
+
+        match problems {
+            0 => self
+                .printer
+                .println("Everything looks good. Happy ideation!"),
+            1 => self.printer.println("Found 1 problem"),
+            _ => self
+                .printer
+                .println(&format!("Found {} problems", problems)),
+        }
+    }
+
+    // Checks the repo, branch and remote, returns whether the repo could be opened
+    fn doctor_repo(&mut self, problems: &mut usize) -> io::Result<bool> {
+        let repo_path = self.cm.config_read(Repo)?;
+        let needs_readme = self
+            .layout()
+            .is_ok_and(|layout| layout == Layout::SingleFile);
+        let repo = match self.git.init(&repo_path) {
+            Err(git_err) => Err(Problem::new(
+                format!("Repo: {} can't be opened: {}", repo_path, git_err.message()),
+                "Set `repo` in the config to your ideas repo, or create one with `eureka init`",
+            )),
+            Ok(()) if needs_readme && !Path::new(&repo_path).join(IDEA_FILE_NAME).exists() => {
+                Err(Problem::new(
+                    format!("Repo: {} has no {}", repo_path, IDEA_FILE_NAME),
+                    "Ideas are appended to it, create it in the root of the repo",
+                ))
+            }
+            Ok(()) => Ok(format!("Repo: {}", repo_path)),
+        };
+        if !self.report(problems, repo)? {
+            return Ok(false);
+        }
+
+        // A missing branch is created when the next idea is captured
+        let branch_name = self.cm.config_read(Branch)?;
+        let branch = match self.git.log(&branch_name) {
+            Ok(_) => format!("Branch: {}", branch_name),
+            Err(_) => format!(
+                "Branch: {} doesn't exist yet, it's created when the next idea is captured",
+                branch_name
+            ),
+        };
+        self.report(problems, Ok(branch))?;
+
+        let remote_name = self.cm.config_read(Remote)?;
+        if remote_name.is_empty() {
+            let remote = String::from("Remote: none, ideas are only committed locally");
+            self.report(problems, Ok(remote))?;
+            return Ok(true);
+        }
+        let remote_url = match self.git.remote_url(&remote_name) {
+            Ok(remote_url) => remote_url,
+            Err(_) => {
+                let problem = Problem::new(
+                    format!("Remote: the repo has no remote named {}", remote_name),
+                    format!(
+                        "Add it with `git remote add {} <url>`, or set `remote` in the config to another remote",
+                        remote_name
+                    ),
+                );
+                self.report(problems, Err(problem))?;
+                return Ok(true);
+            }
+        };
+        self.report(
+            problems,
+            Ok(format!("Remote: {} {}", remote_name, remote_url)),
+        )?;
+
+        let connection = self
+            .with_passphrase(|git| git.connect(&remote_name))
+            .map(|()| format!("Connection: reached {}", remote_url))
+            .map_err(|git_err| {
+                Problem::new(
+                    format!(
+                        "Connection: could not reach {}: {}",
+                        remote_url,
+                        git_err.message()
+                    ),
+                    "Check the URL, your network and the credentials below",
+                )
+            });
+        self.report(problems, connection)?;
+
+        let credentials = remote_credentials(&remote_url);
+        let asks_for = match credentials {
+            RemoteCredentials::SshKey => String::from("an ssh key"),
+            // The token command wins over the env variable
+            RemoteCredentials::Password => {
+                let token_command = self.cm.config_read(HttpsTokenCommand)?;
+                let token_env = self.cm.config_read(HttpsTokenEnv)?;
+                match (token_command.is_empty(), token_env.is_empty()) {
+                    (false, _) => format!("a token, printed by `{}`", token_command),
+                    (true, false) => format!("a token, from ${}", token_env),
+                    (true, true) => {
+                        String::from("a password or token, from your git credential helper")
+                    }
+                }
+            }
+            RemoteCredentials::None => String::from("no credentials"),
+        };
+        self.report(
+            problems,
+            Ok(format!("Credentials: the remote asks for {}", asks_for)),
+        )?;
+
+        if credentials == RemoteCredentials::SshKey {
+            let hint = "Set `ssh_key` in the config to the absolute path of your private key";
+            let ssh_key = self.cm.config_read(SshKey)?;
+            let key = match ssh_key.is_empty() {
+                true => Ok(String::from(
+                    "ssh key: none is configured, only keys in ssh-agent are used",
+                )),
+                false => check_ssh_key(Path::new(&ssh_key))
+                    .map(|()| format!("ssh key: {}", ssh_key))
+                    .map_err(|problem| Problem::new(format!("ssh key: {}", problem), hint)),
+            };
+            self.report(problems, key)?;
+        }
+
+        Ok(true)
+    }
+
+    // Prints the outcome of a check, returns whether it passed
+    fn report(
+        &mut self,
+        problems: &mut usize,
+        result: Result<String, Problem>,
+    ) -> io::Result<bool> {
+        match result {
+            Ok(description) => {
+                self.printer.check(true, &description)?;
+                Ok(true)
+            }
+            Err(problem) => {
+                *problems += 1;
+                self.printer.check(false, &problem.description)?;
+                self.printer.println(&format!("  {}", problem.hint))?;
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn is_config_missing(&self) -> bool {
         self.cm.config_read(Repo).is_err()
     }
 }
+
+// What a remote asks for when ideas are pushed, going by its URL
+#[derive(Debug, Eq, PartialEq)]
+enum RemoteCredentials {
+    SshKey,
+    Password,
+    // Local paths and git:// URLs
+    None,
+}
+
+fn remote_credentials(url: &str) -> RemoteCredentials {
+    // scp-like URLs, e.g. git@github.com:simeg/eureka.git
+    let is_scp_like = !url.contains("://")
+        && url
+            .split_once(':')
+            .is_some_and(|(host, _)| !host.contains('/'));
+
+    if is_https_url(url) {
+        RemoteCredentials::Password
+    } else if url.starts_with("ssh://") || is_scp_like {
+        RemoteCredentials::SshKey
+    } else {
+        RemoteCredentials::None
+    }
+}
+
+// A remote that is only added later may well be an ssh one
+fn needs_ssh_key(remote_url: Option<&str>) -> bool {
+    remote_url.is_none_or(|url| remote_credentials(url) == RemoteCredentials::SshKey)
+}
+
+// Checks that the path is a private key that git can use, returns what's wrong otherwise
+fn check_ssh_key(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(String::from("ssh key path must be absolute"));
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    let contents = contents.trim();
+
+    if contents.starts_with("ssh-") || contents.starts_with("ecdsa-") {
+        return Err(format!(
+            "{} is a public key, use the private key without .pub instead",
+            path.display()
+        ));
+    }
+    if !(contents.starts_with("-----BEGIN ") && contents.ends_with("PRIVATE KEY-----")) {
+        return Err(format!("{} is not a private ssh key", path.display()));
+    }
+
+    Ok(())
+}
+
+fn list_rows(ideas: Vec<Idea>, commits: Vec<LogEntry>) -> Vec<ListRow> {
+    pair_with_commits(ideas, commits, |idea| idea)
+        .into_iter()
+        .map(|(idea, commit)| ListRow {
+            // Ideas captured before entries had a timestamp use the commit date
+            date: idea
+                .captured
+                .or_else(|| commit.as_ref().map(|commit| commit.time)),
+            id: idea.id,
+            short_id: commit.map(|commit| commit.short_id),
+            summary: idea.summary,
+        })
+        .collect()
+}
+
+// The width of a column of `eureka list`, which fits its values and header
+fn column_width<'v>(values: impl Iterator<Item = Option<&'v str>>, header: &str) -> usize {
+    values
+        .flatten()
+        .map(str::len)
+        .max()
+        .unwrap_or_default()
+        .max(header.len())
+}
+
+/// Pairs every idea, newest first, with the commit that captured it. An idea
+/// with an id is paired with the oldest commit with that `Idea-Id` trailer, as
+/// later commits only change it. Ideas captured before ideas had an id are
+/// committed with their summary as the commit subject, so such an idea is paired
+/// with the newest commit with that subject that isn't paired with a newer idea yet.
+fn pair_with_commits<T>(
+    ideas: Vec<T>,
+    commits: Vec<LogEntry>,
+    idea: impl Fn(&T) -> &Idea,
+) -> Vec<(T, Option<LogEntry>)> {
+    let mut commits_by_idea_id: HashMap<String, LogEntry> = HashMap::new();
+    let mut commits_by_subject: HashMap<String, Vec<LogEntry>> = HashMap::new();
+    // Commits are newest first, reverse them so popping returns the newest
+    for commit in commits.into_iter().rev() {
+        match &commit.idea_id {
+            Some(idea_id) => {
+                commits_by_idea_id.entry(idea_id.clone()).or_insert(commit);
+            }
+            None => commits_by_subject
+                .entry(commit.subject.clone())
+                .or_default()
+                .push(commit),
+        }
+    }
+
+    // Ideas are stored in the order they were captured
+    ideas
+        .into_iter()
+        .rev()
+        .map(|stored| {
+            let commit = match &idea(&stored).id {
+                Some(idea_id) => commits_by_idea_id.remove(idea_id),
+                None => commits_by_subject
+                    .get_mut(&idea(&stored).summary)
+                    .and_then(Vec::pop),
+            };
+            (stored, commit)
+        })
+        .collect()
+}
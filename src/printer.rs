@@ -1,5 +1,8 @@
 use std::io;
 use std::io::Write;
+use std::ops::Range;
+
+use crate::render::{Span, Style};
 
 pub trait Print {
     fn print(&mut self, value: &str) -> io::Result<()>;
@@ -10,6 +13,9 @@ pub trait PrintColor {
     fn fts_banner(&mut self) -> io::Result<()>;
     fn input_header(&mut self, value: &str) -> io::Result<()>;
     fn error(&mut self, value: &str) -> io::Result<()>;
+    fn highlight(&mut self, value: &str, matches: &[Range<usize>]) -> io::Result<()>;
+    fn styled(&mut self, spans: &[Span]) -> io::Result<()>;
+    fn check(&mut self, passed: bool, value: &str) -> io::Result<()>;
 }
 
 pub struct Printer<W> {
@@ -57,7 +63,8 @@ impl<W: Write + termcolor::WriteColor> PrintColor for Printer<W> {
         let description = r#"
 This tool requires you to have a repository with a README.md
 in the root folder. The markdown file is where your ideas
-will be stored.
+will be stored. If you don't have one yet, it can be created
+for you, or run `eureka init` instead.
 
 Once first time setup has completed, simply run Eureka again
 to begin writing down ideas.
@@ -83,6 +90,67 @@ to begin writing down ideas.
         self.println_styled(value, opts)?;
         self.writer.flush()
     }
+
+    // Prints the line with the matched ranges, which must be sorted and not overlap, in color
+    fn highlight(&mut self, value: &str, matches: &[Range<usize>]) -> io::Result<()> {
+        let mut color_spec = termcolor::ColorSpec::new();
+        color_spec
+            .set_fg(Some(termcolor::Color::Red))
+            .set_bold(true);
+
+        let mut printed = 0;
+        for range in matches {
+            write!(self.writer, "{}", &value[printed..range.start])?;
+            self.writer.set_color(&color_spec)?;
+            write!(self.writer, "{}", &value[range.clone()])?;
+            self.writer.reset()?;
+            printed = range.end;
+        }
+        writeln!(self.writer, "{}", &value[printed..])
+    }
+
+    // Prints a line of rendered markdown
+    fn styled(&mut self, spans: &[Span]) -> io::Result<()> {
+        for span in spans {
+            let mut color_spec = termcolor::ColorSpec::new();
+            match span.style {
+                Style::Plain => {
+                    write!(self.writer, "{}", span.text)?;
+                    continue;
+                }
+                Style::Heading(1) => color_spec
+                    .set_fg(Some(termcolor::Color::Magenta))
+                    .set_bold(true),
+                Style::Heading(_) => color_spec
+                    .set_fg(Some(termcolor::Color::Cyan))
+                    .set_bold(true),
+                Style::Strong => color_spec.set_bold(true),
+                Style::Emphasis => color_spec.set_italic(true),
+                Style::Code => color_spec.set_fg(Some(termcolor::Color::Yellow)),
+                Style::Bullet => color_spec.set_fg(Some(termcolor::Color::Blue)),
+                Style::Checked => color_spec.set_fg(Some(termcolor::Color::Green)),
+                Style::Unchecked => color_spec.set_fg(Some(termcolor::Color::Red)),
+            };
+            self.writer.set_color(&color_spec)?;
+            write!(self.writer, "{}", span.text)?;
+            self.writer.reset()?;
+        }
+        writeln!(self.writer)
+    }
+
+    // Prints the outcome of a check, marked as passed or failed
+    fn check(&mut self, passed: bool, value: &str) -> io::Result<()> {
+        let (mark, color) = match passed {
+            true => ("✔", termcolor::Color::Green),
+            false => ("✘", termcolor::Color::Red),
+        };
+        let mut color_spec = termcolor::ColorSpec::new();
+        color_spec.set_fg(Some(color)).set_bold(true);
+        self.writer.set_color(&color_spec)?;
+        write!(self.writer, "{}", mark)?;
+        self.writer.reset()?;
+        writeln!(self.writer, " {}", value)
+    }
 }
 
 impl<W: Write + termcolor::WriteColor> Printer<W> {
@@ -99,6 +167,7 @@ impl<W: Write + termcolor::WriteColor> Printer<W> {
 #[cfg(test)]
 mod tests {
     use crate::printer::{Print, PrintColor, PrintOptions, Printer};
+    use crate::render::{Span, Style};
 
     #[test]
     fn test_printer__print__success() {
@@ -146,7 +215,8 @@ mod tests {
 
 This tool requires you to have a repository with a README.md
 in the root folder. The markdown file is where your ideas
-will be stored.
+will be stored. If you don't have one yet, it can be created
+for you, or run `eureka init` instead.
 
 Once first time setup has completed, simply run Eureka again
 to begin writing down ideas.";
@@ -182,6 +252,59 @@ to begin writing down ideas.";
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_printer__highlight__success() {
+        let mut output = termcolor::Ansi::new(vec![]);
+        let mut printer = Printer::new(&mut output);
+
+        printer
+            .highlight("some-value other-value", &[5..10, 17..22])
+            .unwrap();
+
+        let actual = String::from_utf8(output.into_inner()).unwrap();
+        let expected = "some-\u{1b}[0m\u{1b}[1m\u{1b}[31mvalue\u{1b}[0m other-\u{1b}[0m\u{1b}[1m\u{1b}[31mvalue\u{1b}[0m\n";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_printer__styled__success() {
+        let mut output = termcolor::Ansi::new(vec![]);
+        let mut printer = Printer::new(&mut output);
+
+        printer
+            .styled(&[
+                Span {
+                    text: String::from("some "),
+                    style: Style::Plain,
+                },
+                Span {
+                    text: String::from("value"),
+                    style: Style::Strong,
+                },
+            ])
+            .unwrap();
+
+        let actual = String::from_utf8(output.into_inner()).unwrap();
+        let expected = "some \u{1b}[0m\u{1b}[1mvalue\u{1b}[0m\n";
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_printer__check__success() {
+        let mut output = termcolor::Ansi::new(vec![]);
+        let mut printer = Printer::new(&mut output);
+
+        printer.check(true, "some-value").unwrap();
+        printer.check(false, "other-value").unwrap();
+
+        let actual = String::from_utf8(output.into_inner()).unwrap();
+        let expected = "\u{1b}[0m\u{1b}[1m\u{1b}[32m✔\u{1b}[0m some-value\n\u{1b}[0m\u{1b}[1m\u{1b}[31m✘\u{1b}[0m other-value\n";
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_printer__println_styled__success() {
         let mut output_1 = termcolor::Ansi::new(vec![]);
@@ -1,3 +1,5 @@
+use std::error::Error;
+use std::fmt;
 use std::io::ErrorKind;
 use std::path::PathBuf;
 use std::process::Command;
@@ -6,30 +8,119 @@ use std::{env, fs, io};
 pub trait ProgramOpener {
     fn open_editor(&self, file_path: &str) -> io::Result<()>;
     fn open_pager(&self, file_path: &str) -> io::Result<()>;
+    // The commands that will be run, failing if their program can't be found
+    fn editor_command(&self) -> io::Result<String>;
+    fn pager_command(&self) -> io::Result<String>;
 }
 
 #[derive(Default)]
-pub struct ProgramAccess;
+pub struct ProgramAccess {
+    // Commands from the config, which take precedence over the env variables
+    editor: String,
+    pager: String,
+}
+
+/// The program exited with a non-zero status, e.g. when quitting vim with `:cq`.
+/// It's wrapped in the `io::Error` returned by `ProgramOpener`.
+#[derive(Debug)]
+pub struct ExitStatusError {
+    pub program: String,
+    // None if the program was terminated by a signal
+    pub code: Option<i32>,
+}
+
+impl ExitStatusError {
+    pub fn is_cause_of(err: &io::Error) -> bool {
+        err.get_ref().is_some_and(|inner| inner.is::<Self>())
+    }
+}
+
+impl fmt::Display for ExitStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "{} exited with status {}", self.program, code),
+            None => write!(f, "{} was terminated by a signal", self.program),
+        }
+    }
+}
+
+impl Error for ExitStatusError {}
 
 impl ProgramOpener for ProgramAccess {
     fn open_editor(&self, file_path: &str) -> io::Result<()> {
-        self.open_with_fallback(file_path, "EDITOR", "vi")
+        self.open_with_fallback(file_path, self.configured_editor(), "vi")
     }
 
     fn open_pager(&self, file_path: &str) -> io::Result<()> {
-        self.open_with_fallback(file_path, "PAGER", "less")
+        self.open_with_fallback(file_path, self.configured_pager(), "less")
+    }
+
+    fn editor_command(&self) -> io::Result<String> {
+        self.resolve(self.configured_editor(), "vi")
+    }
+
+    fn pager_command(&self) -> io::Result<String> {
+        self.resolve(self.configured_pager(), "less")
     }
 }
 
 impl ProgramAccess {
-    fn open_with_fallback(&self, file_path: &str, env_var: &str, fallback: &str) -> io::Result<()> {
-        let program = env::var(env_var)
-            .map(PathBuf::from)
-            .or_else(|_| self.get_if_available(fallback))?;
+    pub fn new(editor: &str, pager: &str) -> Self {
+        Self {
+            editor: editor.to_owned(),
+            pager: pager.to_owned(),
+        }
+    }
+
+    fn open_with_fallback(
+        &self,
+        file_path: &str,
+        command: Option<String>,
+        fallback: &str,
+    ) -> io::Result<()> {
+        let (program, args) = match command {
+            Some(command) => split_command(&command)?,
+            None => (
+                self.get_if_available(fallback)?.display().to_string(),
+                Vec::new(),
+            ),
+        };
 
         // Make sure file exists
         fs::metadata(file_path)?;
-        Command::new(program).arg(file_path).status().map(|_| ())
+        let status = Command::new(&program).args(&args).arg(file_path).status()?;
+        if !status.success() {
+            return Err(io::Error::other(ExitStatusError {
+                program,
+                code: status.code(),
+            }));
+        }
+        Ok(())
+    }
+
+    fn configured_editor(&self) -> Option<String> {
+        first_set([
+            Some(self.editor.clone()),
+            env::var("VISUAL").ok(),
+            env::var("EDITOR").ok(),
+            git_editor(),
+        ])
+    }
+
+    fn configured_pager(&self) -> Option<String> {
+        first_set([Some(self.pager.clone()), env::var("PAGER").ok()])
+    }
+
+    fn resolve(&self, command: Option<String>, fallback: &str) -> io::Result<String> {
+        let command = command.unwrap_or_else(|| fallback.to_string());
+        let (program, _) = split_command(&command)?;
+        self.get_if_available(&program).map_err(|_| {
+            io::Error::new(
+                ErrorKind::NotFound,
+                format!("{} is not installed or not on your PATH", program),
+            )
+        })?;
+        Ok(command)
     }
 
     fn get_if_available(&self, program: &str) -> io::Result<PathBuf> {
@@ -37,10 +128,45 @@ impl ProgramAccess {
     }
 }
 
+// Unset and blank commands are skipped
+fn first_set<const N: usize>(commands: [Option<String>; N]) -> Option<String> {
+    commands
+        .into_iter()
+        .flatten()
+        .find(|command| !command.trim().is_empty())
+}
+
+// Splits a command like `code --wait` into the program and its arguments, the way a shell would
+fn split_command(command: &str) -> io::Result<(String, Vec<String>)> {
+    let mut words = shell_words::split(command).map_err(|err| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Can't run \"{}\": {}", command, err),
+        )
+    })?;
+    if words.is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Can't run \"{}\": no program given", command),
+        ));
+    }
+    let program = words.remove(0);
+    Ok((program, words))
+}
+
+// The editor configured for git in the global or system git config
+fn git_editor() -> Option<String> {
+    git2::Config::open_default()
+        .and_then(|config| config.get_string("core.editor"))
+        .ok()
+}
+
 #[allow(non_snake_case)]
 #[cfg(test)]
 mod tests {
-    use crate::program_access::{ProgramAccess, ProgramOpener};
+    use crate::program_access::{
+        first_set, split_command, ExitStatusError, ProgramAccess, ProgramOpener,
+    };
     use std::env;
 
     type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -68,15 +194,13 @@ mod tests {
         let program_access = ProgramAccess::default();
         let tmp_file = tempfile::NamedTempFile::new()?;
         let file_path = tmp_file.path().to_str().unwrap();
-        env::set_var("READER_ENV_VAR", "echo");
 
         program_access.open_with_fallback(
             file_path,
-            "READER_ENV_VAR",
+            Some(String::from("echo")),
             "some-non-existing-program",
         )?;
 
-        env::remove_var("READER_ENV_VARIABLE");
         Ok(())
     }
 
@@ -85,9 +209,84 @@ mod tests {
         let program_access = ProgramAccess::default();
         let tmp_file = tempfile::NamedTempFile::new()?;
         let file_path = tmp_file.path().to_str().unwrap();
-        env::remove_var("THIS_ENV_VAR");
 
-        program_access.open_with_fallback(file_path, "THIS_ENV_VAR", "echo")?;
+        program_access.open_with_fallback(file_path, None, "echo")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_access__open_with_fallback__non_zero_exit() -> TestResult {
+        let program_access = ProgramAccess::default();
+        let tmp_file = tempfile::NamedTempFile::new()?;
+        let file_path = tmp_file.path().to_str().unwrap();
+
+        let actual = program_access
+            .open_with_fallback(file_path, None, "false")
+            .unwrap_err();
+
+        assert!(ExitStatusError::is_cause_of(&actual));
+        assert!(actual.to_string().ends_with("false exited with status 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_access__open_with_fallback__with_arguments() -> TestResult {
+        let program_access = ProgramAccess::default();
+        let tmp_file = tempfile::NamedTempFile::new()?;
+        let file_path = tmp_file.path().to_str().unwrap();
+
+        // The file is passed after the arguments, so `test -f` only succeeds if it exists
+        program_access.open_with_fallback(
+            file_path,
+            Some(String::from("test -f")),
+            "some-non-existing-program",
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_access__split_command() {
+        assert_eq!(
+            split_command("code --wait").unwrap(),
+            (String::from("code"), vec![String::from("--wait")])
+        );
+        assert_eq!(
+            split_command("nvim -c 'set ft=markdown'").unwrap(),
+            (
+                String::from("nvim"),
+                vec![String::from("-c"), String::from("set ft=markdown")]
+            )
+        );
+        assert_eq!(
+            split_command("\"/opt/My Editor/bin/edit\"").unwrap(),
+            (String::from("/opt/My Editor/bin/edit"), Vec::new())
+        );
+        assert!(split_command("nvim -c 'unclosed").is_err());
+    }
+
+    #[test]
+    fn test_program_access__first_set() {
+        let actual = first_set([
+            Some(String::new()),
+            None,
+            Some(String::from("  ")),
+            Some(String::from("nano")),
+            Some(String::from("vim")),
+        ]);
+
+        assert_eq!(actual, Some(String::from("nano")));
+        assert_eq!(first_set([None, Some(String::new())]), None);
+    }
+
+    #[test]
+    fn test_program_access__open_editor__configured() -> TestResult {
+        let program_access = ProgramAccess::new("test -f", "");
+        let tmp_file = tempfile::NamedTempFile::new()?;
+        let file_path = tmp_file.path().to_str().unwrap();
+
+        program_access.open_editor(file_path)?;
 
         Ok(())
     }
@@ -119,4 +318,25 @@ mod tests {
         env::set_var("PAGER", pager_value);
         Ok(())
     }
+
+    #[test]
+    fn test_program_access__editor_command__configured() -> TestResult {
+        let program_access = ProgramAccess::new("test -f", "echo");
+
+        assert_eq!(program_access.editor_command()?, "test -f");
+        assert_eq!(program_access.pager_command()?, "echo");
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_access__editor_command__not_installed() {
+        let program_access = ProgramAccess::new("some-missing-editor --wait", "");
+
+        let actual = program_access.editor_command();
+
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "some-missing-editor is not installed or not on your PATH"
+        );
+    }
 }
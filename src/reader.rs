@@ -1,16 +1,39 @@
 use std::io;
+use std::io::{ErrorKind, Read};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+// The body argument that reads the body from stdin instead
+const BODY_FROM_STDIN: &str = "-";
 
 pub trait ReadInput {
     fn read_input(&mut self) -> io::Result<String>;
+    // Reads lines until an empty line or the end of input, e.g. Ctrl-D
+    fn read_lines(&mut self) -> io::Result<String>;
+    // Reads a line without showing what's typed, e.g. a passphrase
+    fn read_secret(&mut self) -> io::Result<String>;
 }
 
 pub struct Reader<R> {
     reader: R,
+    // Whether the input is typed in a terminal, which would echo secrets
+    is_terminal: bool,
 }
 
 impl<R> Reader<R> {
     pub fn new(reader: R) -> Self {
-        Self { reader }
+        Self {
+            reader,
+            is_terminal: false,
+        }
+    }
+
+    pub fn terminal(reader: R) -> Self {
+        Self {
+            reader,
+            is_terminal: true,
+        }
     }
 }
 
@@ -20,12 +43,99 @@ impl<R: io::BufRead> ReadInput for Reader<R> {
         self.reader.read_line(&mut input)?;
         Ok(input.trim().to_string())
     }
+
+    fn read_lines(&mut self) -> io::Result<String> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            // Indentation is kept for lists and code
+            lines.push(line.trim_end().to_string());
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn read_secret(&mut self) -> io::Result<String> {
+        if self.is_terminal {
+            terminal::enable_raw_mode()?;
+            let secret = read_hidden_line();
+            terminal::disable_raw_mode()?;
+            return secret;
+        }
+
+        // Spaces can be part of a secret
+        let mut input = String::new();
+        self.reader.read_line(&mut input)?;
+        Ok(input.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+// Reads keys until Enter. Raw mode doesn't echo them, but it doesn't handle
+// Backspace or Ctrl-C either
+fn read_hidden_line() -> io::Result<String> {
+    let mut line = String::new();
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => return Ok(line),
+            KeyCode::Esc => return Err(io::Error::from(ErrorKind::Interrupted)),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(io::Error::from(ErrorKind::Interrupted))
+            }
+            KeyCode::Char(c) => line.push(c),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The body given with `-b`, read from the input when it's `-`, e.g.
+/// `echo body | eureka -m summary -b -`. Input that isn't asked for is left unread,
+/// as git hooks and editors often run eureka with stdin piped.
+pub fn read_body(body: Option<&str>, mut input: impl Read) -> io::Result<Option<String>> {
+    match body {
+        Some(BODY_FROM_STDIN) => {
+            let mut body = String::new();
+            input.read_to_string(&mut body)?;
+            Ok(Some(body))
+        }
+        body => Ok(body.map(String::from)),
+    }
 }
 
 #[allow(non_snake_case)]
 #[cfg(test)]
 mod tests {
-    use crate::reader::{ReadInput, Reader};
+    use crate::reader::{read_body, ReadInput, Reader};
+
+    #[test]
+    fn test_reader__read_body__from_stdin() {
+        let input = b"some-body\n";
+
+        let actual = read_body(Some("-"), &input[..]).unwrap();
+
+        assert_eq!(actual, Some(String::from("some-body\n")));
+    }
+
+    #[test]
+    fn test_reader__read_body__ignores_stdin() {
+        let input = b"some-hook-input\n";
+
+        assert_eq!(
+            read_body(Some("some-body"), &input[..]).unwrap(),
+            Some(String::from("some-body"))
+        );
+        assert_eq!(read_body(None, &input[..]).unwrap(), None);
+    }
 
     #[test]
     fn test_reader__read_input__success() {
@@ -37,4 +147,35 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_reader__read_lines__until_empty_line() {
+        let input = b"first line  \n  - indented item\n\nnot read\n";
+        let mut reader = Reader::new(&input[..]);
+
+        let actual = reader.read_lines().unwrap();
+        let expected = "first line\n  - indented item".to_string();
+
+        assert_eq!(actual, expected);
+        assert_eq!(reader.read_input().unwrap(), "not read");
+    }
+
+    #[test]
+    fn test_reader__read_lines__until_end_of_input() {
+        let input = b"only line";
+        let mut reader = Reader::new(&input[..]);
+
+        assert_eq!(reader.read_lines().unwrap(), "only line");
+        assert_eq!(reader.read_lines().unwrap(), "");
+    }
+
+    #[test]
+    fn test_reader__read_secret__keeps_spaces() {
+        let input = b" some secret \nnot read\n";
+        let mut reader = Reader::new(&input[..]);
+
+        let actual = reader.read_secret().unwrap();
+
+        assert_eq!(actual, " some secret ");
+    }
 }
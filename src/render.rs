@@ -0,0 +1,250 @@
+const CODE_FENCE: &str = "```";
+const CODE_BLOCK_INDENT: &str = "    ";
+
+const BULLET: &str = "• ";
+const CHECKED: &str = "☑ ";
+const UNCHECKED: &str = "☐ ";
+
+/// How a span of rendered markdown is printed
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Style {
+    Plain,
+    Heading(usize),
+    Strong,
+    Emphasis,
+    Code,
+    Bullet,
+    Checked,
+    Unchecked,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    fn new(text: &str, style: Style) -> Self {
+        Self {
+            text: text.to_string(),
+            style,
+        }
+    }
+}
+
+/// Renders markdown for the terminal, one line of spans per line of output.
+/// Headings, lists, checkboxes, emphasis and code are styled, everything else
+/// is kept as is.
+pub fn render(markdown: &str) -> Vec<Vec<Span>> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with(CODE_FENCE) {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            lines.push(vec![Span::new(
+                &format!("{}{}", CODE_BLOCK_INDENT, line),
+                Style::Code,
+            )]);
+        } else if let Some((level, heading)) = parse_heading(line) {
+            lines.push(vec![Span::new(heading, Style::Heading(level))]);
+        } else if let Some((indent, marker, item)) = parse_list_item(line) {
+            let mut spans = Vec::new();
+            if !indent.is_empty() {
+                spans.push(Span::new(indent, Style::Plain));
+            }
+            spans.push(marker);
+            spans.extend(render_inline(item));
+            lines.push(spans);
+        } else {
+            lines.push(render_inline(line));
+        }
+    }
+
+    lines
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    match &line[level..] {
+        "" => Some((level, "")),
+        rest if rest.starts_with(' ') => Some((level, rest.trim())),
+        _ => None,
+    }
+}
+
+// Returns the indentation, the styled marker and the rest of the item
+fn parse_list_item(line: &str) -> Option<(&str, Span, &str)> {
+    let item = line.trim_start();
+    let indent = &line[..line.len() - item.len()];
+
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = item.strip_prefix(bullet) {
+            if let Some(rest) = rest.strip_prefix("[ ] ") {
+                return Some((indent, Span::new(UNCHECKED, Style::Unchecked), rest));
+            }
+            if let Some(rest) = rest
+                .strip_prefix("[x] ")
+                .or_else(|| rest.strip_prefix("[X] "))
+            {
+                return Some((indent, Span::new(CHECKED, Style::Checked), rest));
+            }
+            return Some((indent, Span::new(BULLET, Style::Bullet), rest));
+        }
+    }
+
+    // Ordered lists keep their numbers
+    let digits = item.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && item[digits..].starts_with(". ") {
+        let (number, rest) = item.split_at(digits + 2);
+        return Some((indent, Span::new(number, Style::Bullet), rest));
+    }
+
+    None
+}
+
+// Styles `code`, **strong** and *emphasis* in a line
+fn render_inline(line: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        let styled = match c {
+            '`' => delimited(rest, "`", Style::Code),
+            '*' => delimited(rest, "**", Style::Strong)
+                .or_else(|| delimited(rest, "*", Style::Emphasis)),
+            // Underscores only emphasize whole words, so snake_case is left alone
+            '_' if plain.is_empty() || plain.ends_with(char::is_whitespace) => {
+                delimited(rest, "__", Style::Strong)
+                    .or_else(|| delimited(rest, "_", Style::Emphasis))
+            }
+            _ => None,
+        };
+
+        match styled {
+            Some((span, remaining)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::new(&std::mem::take(&mut plain), Style::Plain));
+                }
+                spans.push(span);
+                rest = remaining;
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    if !plain.is_empty() || spans.is_empty() {
+        spans.push(Span::new(&plain, Style::Plain));
+    }
+    spans
+}
+
+// Returns the span between the delimiters at the start of `text` and the text after it
+fn delimited<'t>(text: &'t str, delimiter: &str, style: Style) -> Option<(Span, &'t str)> {
+    let inner = text.strip_prefix(delimiter)?;
+    let end = inner.find(delimiter)?;
+    if end == 0 || inner.starts_with(' ') {
+        return None;
+    }
+    Some((
+        Span::new(&inner[..end], style),
+        &inner[end + delimiter.len()..],
+    ))
+}
+
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod tests {
+    use crate::render::{render, Span, Style};
+
+    fn span(text: &str, style: Style) -> Span {
+        Span {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    #[test]
+    fn test_render__headings_and_lists() {
+        let actual =
+            render("# Ideas\n\n## some-summary\n- first\n  - [ ] todo\n  - [x] done\n1. one");
+
+        assert_eq!(
+            actual,
+            vec![
+                vec![span("Ideas", Style::Heading(1))],
+                vec![span("", Style::Plain)],
+                vec![span("some-summary", Style::Heading(2))],
+                vec![span("• ", Style::Bullet), span("first", Style::Plain)],
+                vec![
+                    span("  ", Style::Plain),
+                    span("☐ ", Style::Unchecked),
+                    span("todo", Style::Plain)
+                ],
+                vec![
+                    span("  ", Style::Plain),
+                    span("☑ ", Style::Checked),
+                    span("done", Style::Plain)
+                ],
+                vec![span("1. ", Style::Bullet), span("one", Style::Plain)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render__inline() {
+        let actual =
+            render("some **strong**, *emphasis*, `code` and _emphasis_ in snake_case_words");
+
+        assert_eq!(
+            actual,
+            vec![vec![
+                span("some ", Style::Plain),
+                span("strong", Style::Strong),
+                span(", ", Style::Plain),
+                span("emphasis", Style::Emphasis),
+                span(", ", Style::Plain),
+                span("code", Style::Code),
+                span(" and ", Style::Plain),
+                span("emphasis", Style::Emphasis),
+                span(" in snake_case_words", Style::Plain),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_render__unclosed_delimiters() {
+        let actual = render("2 * 3 and `unclosed");
+
+        assert_eq!(
+            actual,
+            vec![vec![span("2 * 3 and `unclosed", Style::Plain)]]
+        );
+    }
+
+    #[test]
+    fn test_render__code_block() {
+        let actual = render("```rust\n# not a heading\n- not a list\n```\n#hashtag");
+
+        assert_eq!(
+            actual,
+            vec![
+                vec![span("    # not a heading", Style::Code)],
+                vec![span("    - not a list", Style::Code)],
+                vec![span("#hashtag", Style::Plain)],
+            ]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use std::io;
+use std::io::{Error, ErrorKind};
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::idea::Idea;
+
+const OPERATOR_OR: &str = "OR";
+const OPERATOR_AND: &str = "AND";
+const OPERATOR_NOT: &str = "NOT";
+
+/// A search query of terms that must all match. Terms are separated by
+/// whitespace, `"quoted phrases"` are a single term, `-term` or `NOT term`
+/// must not match and `OR` separates alternative groups of terms.
+#[derive(Debug)]
+pub struct Query {
+    alternatives: Vec<Vec<Term>>,
+}
+
+#[derive(Debug)]
+struct Term {
+    pattern: Regex,
+    negated: bool,
+}
+
+impl Query {
+    /// Terms are matched literally unless `is_regex` is set, and ignore case
+    /// unless `is_case_sensitive` is set.
+    pub fn parse(query: &str, is_regex: bool, is_case_sensitive: bool) -> io::Result<Self> {
+        let mut alternatives = vec![Vec::new()];
+        let mut negate_next = false;
+
+        for (token, is_quoted) in tokenize(query) {
+            if !is_quoted {
+                match token.as_str() {
+                    OPERATOR_OR => {
+                        alternatives.push(Vec::new());
+                        continue;
+                    }
+                    OPERATOR_AND => continue,
+                    OPERATOR_NOT => {
+                        negate_next = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            let (token, negated) = match token.strip_prefix('-') {
+                Some(rest) if !is_quoted && !rest.is_empty() => (rest.to_string(), true),
+                _ => (token, negate_next),
+            };
+            negate_next = false;
+
+            let pattern = if is_regex {
+                token
+            } else {
+                regex::escape(&token)
+            };
+            let pattern = RegexBuilder::new(&pattern)
+                .case_insensitive(!is_case_sensitive)
+                .build()
+                .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+
+            alternatives
+                .last_mut()
+                .unwrap()
+                .push(Term { pattern, negated });
+        }
+
+        alternatives.retain(|terms| !terms.is_empty());
+        if alternatives.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Search query can't be empty",
+            ));
+        }
+
+        Ok(Self { alternatives })
+    }
+
+    pub fn is_match(&self, idea: &Idea) -> bool {
+        let text = format!("{}\n{}", idea.summary, idea.body);
+
+        self.alternatives.iter().any(|terms| {
+            terms
+                .iter()
+                .all(|term| term.pattern.is_match(&text) != term.negated)
+        })
+    }
+
+    /// Returns the sorted, non-overlapping ranges of the line that match a term
+    pub fn find_matches(&self, line: &str) -> Vec<Range<usize>> {
+        let mut matches: Vec<Range<usize>> = self
+            .alternatives
+            .iter()
+            .flatten()
+            .filter(|term| !term.negated)
+            .flat_map(|term| term.pattern.find_iter(line).map(|found| found.range()))
+            .filter(|range| !range.is_empty())
+            .collect();
+        matches.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in matches {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+}
+
+// Splits the query on whitespace, keeping quoted phrases together
+fn tokenize(query: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut is_quoted = false;
+    let mut in_quotes = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                is_quoted = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !token.is_empty() {
+                    tokens.push((std::mem::take(&mut token), is_quoted));
+                }
+                is_quoted = false;
+            }
+            c => token.push(c),
+        }
+    }
+
+    if !token.is_empty() {
+        tokens.push((token, is_quoted));
+    }
+    tokens
+}
+
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod tests {
+    use crate::idea::{Idea, Status};
+    use crate::search::{tokenize, Query};
+
+    fn idea(summary: &str, body: &str) -> Idea {
+        Idea {
+            id: None,
+            summary: summary.to_string(),
+            body: body.to_string(),
+            captured: None,
+            status: Status::Open,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_search__tokenize() {
+        assert_eq!(
+            tokenize(r#"some "quoted phrase"  -other"#),
+            vec![
+                (String::from("some"), false),
+                (String::from("quoted phrase"), true),
+                (String::from("-other"), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search__is_match__case_insensitive() {
+        let query = Query::parse("RUST", false, false).unwrap();
+
+        assert!(query.is_match(&idea("Learn rust", "")));
+        assert!(query.is_match(&idea("Learn", "some rust in the body")));
+        assert!(!query.is_match(&idea("Learn go", "")));
+    }
+
+    #[test]
+    fn test_search__is_match__case_sensitive() {
+        let query = Query::parse("Rust", false, true).unwrap();
+
+        assert!(query.is_match(&idea("Learn Rust", "")));
+        assert!(!query.is_match(&idea("Learn rust", "")));
+    }
+
+    #[test]
+    fn test_search__is_match__boolean_terms() {
+        let query = Query::parse("rust AND cli -tui OR \"blog post\"", false, false).unwrap();
+
+        assert!(query.is_match(&idea("A rust cli", "")));
+        assert!(!query.is_match(&idea("A rust cli", "with a tui")));
+        assert!(!query.is_match(&idea("A rust library", "")));
+        assert!(query.is_match(&idea("Write a blog post", "")));
+        assert!(!query.is_match(&idea("Write a post for the blog", "")));
+
+        let query = Query::parse("rust NOT tui", false, false).unwrap();
+        assert!(!query.is_match(&idea("A rust tui", "")));
+    }
+
+    #[test]
+    fn test_search__is_match__regex() {
+        let query = Query::parse(r"v\d+\.\d+", true, false).unwrap();
+
+        assert!(query.is_match(&idea("Release v2.1", "")));
+        assert!(!query.is_match(&idea("Release v2", "")));
+
+        // Without the regex flag the pattern is matched literally
+        let query = Query::parse("v2.1", false, false).unwrap();
+        assert!(!query.is_match(&idea("Release v201", "")));
+    }
+
+    #[test]
+    fn test_search__parse__invalid() {
+        assert!(Query::parse("", false, false).is_err());
+        assert!(Query::parse("OR", false, false).is_err());
+        assert!(Query::parse("(unclosed", true, false).is_err());
+    }
+
+    #[test]
+    fn test_search__find_matches() {
+        let query = Query::parse("rust ust cli -tui", false, false).unwrap();
+
+        let actual = query.find_matches("Rust cli, not a tui");
+
+        assert_eq!(actual, vec![0..4, 5..8]);
+    }
+}
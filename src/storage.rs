@@ -0,0 +1,505 @@
+use std::fmt;
+use std::io::{Error, ErrorKind, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+use crate::idea::{parse_idea_sections, parse_ideas, Idea};
+
+pub const IDEA_FILE_NAME: &str = "README.md";
+const IDEAS_DIR_NAME: &str = "ideas";
+const MAX_SLUG_LENGTH: usize = 50;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Layout {
+    // All ideas are appended to README.md
+    #[default]
+    SingleFile,
+    // Every idea is written to its own file in ideas/YYYY/MM/
+    PerIdea,
+}
+
+impl Layout {
+    pub const SINGLE_FILE: &'static str = "single-file";
+    pub const PER_IDEA: &'static str = "per-idea";
+}
+
+impl FromStr for Layout {
+    type Err = io::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            Layout::SINGLE_FILE => Ok(Layout::SingleFile),
+            Layout::PER_IDEA => Ok(Layout::PerIdea),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Unknown layout \"{}\", use \"{}\" or \"{}\"",
+                    value,
+                    Layout::SINGLE_FILE,
+                    Layout::PER_IDEA
+                ),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Layout::SingleFile => write!(f, "{}", Layout::SINGLE_FILE),
+            Layout::PerIdea => write!(f, "{}", Layout::PER_IDEA),
+        }
+    }
+}
+
+/// An idea and where in the repo it is stored
+#[derive(Debug)]
+pub struct StoredIdea {
+    pub idea: Idea,
+    // Relative to the repo
+    file_path: PathBuf,
+    lines: Range<usize>,
+}
+
+/// Writes the idea to the repo and returns the paths, relative to the repo,
+/// of the files that were written.
+pub fn save_idea(
+    repo_path: &Path,
+    layout: Layout,
+    readme_index: bool,
+    idea: &Idea,
+) -> io::Result<Vec<PathBuf>> {
+    match layout {
+        Layout::SingleFile => {
+            append_to_file(&repo_path.join(IDEA_FILE_NAME), &idea.render())?;
+            Ok(vec![PathBuf::from(IDEA_FILE_NAME)])
+        }
+        Layout::PerIdea => {
+            let mut file_paths = vec![write_idea_file(repo_path, idea)?];
+            if readme_index {
+                file_paths.extend(write_index(repo_path)?);
+            }
+            Ok(file_paths)
+        }
+    }
+}
+
+/// Reads all ideas of the repo, oldest first.
+pub fn read_ideas(repo_path: &Path, layout: Layout) -> io::Result<Vec<Idea>> {
+    Ok(read_stored_ideas(repo_path, layout)?
+        .into_iter()
+        .map(|stored| stored.idea)
+        .collect())
+}
+
+/// Reads all ideas of the repo, oldest first, with where they are stored.
+pub fn read_stored_ideas(repo_path: &Path, layout: Layout) -> io::Result<Vec<StoredIdea>> {
+    let file_paths = match layout {
+        Layout::SingleFile => vec![PathBuf::from(IDEA_FILE_NAME)],
+        Layout::PerIdea => idea_files(repo_path)?,
+    };
+
+    let mut ideas = Vec::new();
+    for file_path in file_paths {
+        let contents = fs::read_to_string(repo_path.join(&file_path))?;
+        ideas.extend(
+            parse_idea_sections(&contents)
+                .into_iter()
+                .map(|(idea, lines)| StoredIdea {
+                    idea,
+                    file_path: file_path.clone(),
+                    lines,
+                }),
+        );
+    }
+
+    // Ideas captured on the same day are sorted by the time of day, not by their slug
+    if layout == Layout::PerIdea {
+        ideas.sort_by(|a, b| {
+            a.idea
+                .captured
+                .cmp(&b.idea.captured)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+    }
+    Ok(ideas)
+}
+
+/// Replaces the stored idea with the given idea and returns the paths, relative
+/// to the repo, of the files that were written.
+pub fn replace_idea(
+    repo_path: &Path,
+    layout: Layout,
+    readme_index: bool,
+    stored: &StoredIdea,
+    idea: &Idea,
+) -> io::Result<Vec<PathBuf>> {
+    let full_path = repo_path.join(&stored.file_path);
+    let contents = fs::read_to_string(&full_path)?;
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let mut replaced = String::new();
+    for line in &lines[..stored.lines.start] {
+        replaced.push_str(line);
+        replaced.push('\n');
+    }
+    replaced.push_str(&idea.render());
+    // Keep the empty line that separates the idea from the next one
+    if stored.lines.end < lines.len() {
+        replaced.push('\n');
+        for line in &lines[stored.lines.end..] {
+            replaced.push_str(line);
+            replaced.push('\n');
+        }
+    }
+    fs::write(&full_path, replaced)?;
+
+    let mut file_paths = vec![stored.file_path.clone()];
+    if layout == Layout::PerIdea && readme_index {
+        file_paths.extend(write_index(repo_path)?);
+    }
+    Ok(file_paths)
+}
+
+// Paths relative to the repo, sorted by the date the file names start with
+fn idea_files(repo_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut idea_paths = Vec::new();
+    let mut dirs = vec![PathBuf::from(IDEAS_DIR_NAME)];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(repo_path.join(&dir)) {
+            Ok(entries) => entries,
+            // No idea has been captured yet
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            let path = dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "md") {
+                idea_paths.push(path);
+            }
+        }
+    }
+
+    idea_paths.sort();
+    Ok(idea_paths)
+}
+
+// ideas/YYYY/MM/YYYY-MM-DD-<slug>.md, with a number appended if the file already exists
+fn new_idea_path(repo_path: &Path, idea: &Idea) -> PathBuf {
+    let captured = idea
+        .captured
+        .unwrap_or_else(|| chrono::Local::now().fixed_offset());
+    let dir = Path::new(IDEAS_DIR_NAME)
+        .join(captured.format("%Y").to_string())
+        .join(captured.format("%m").to_string());
+    let name = format!("{}-{}", captured.format("%Y-%m-%d"), slugify(&idea.summary));
+
+    let mut idea_path = dir.join(format!("{}.md", name));
+    let mut count = 1;
+    while repo_path.join(&idea_path).exists() {
+        count += 1;
+        idea_path = dir.join(format!("{}-{}.md", name, count));
+    }
+    idea_path
+}
+
+// Returns the paths, relative to the repo, of the files that were written
+fn write_index(repo_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut file_paths = move_readme_ideas(repo_path)?;
+    let mut index = String::from("# Ideas\n\n");
+
+    // Newest ideas first
+    for stored in read_stored_ideas(repo_path, Layout::PerIdea)?.iter().rev() {
+        let date = stored
+            .idea
+            .captured
+            .map(|captured| format!("{} ", captured.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        index.push_str(&format!(
+            "- {}[{}]({})\n",
+            date,
+            stored.idea.summary,
+            // Links use forward slashes on every platform
+            stored.file_path.to_string_lossy().replace('\\', "/")
+        ));
+    }
+
+    fs::write(repo_path.join(IDEA_FILE_NAME), index)?;
+    file_paths.push(PathBuf::from(IDEA_FILE_NAME));
+    Ok(file_paths)
+}
+
+// Ideas captured with the single file layout are still in README.md. They are
+// moved to their own files, so that the index doesn't overwrite them.
+fn move_readme_ideas(repo_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let contents = match fs::read_to_string(repo_path.join(IDEA_FILE_NAME)) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    parse_ideas(&contents)
+        .iter()
+        .map(|idea| write_idea_file(repo_path, idea))
+        .collect()
+}
+
+// Writes the idea to a new file and returns its path, relative to the repo
+fn write_idea_file(repo_path: &Path, idea: &Idea) -> io::Result<PathBuf> {
+    let idea_path = new_idea_path(repo_path, idea);
+    let full_path = repo_path.join(&idea_path);
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&full_path, idea.render())?;
+    Ok(idea_path)
+}
+
+fn slugify(summary: &str) -> String {
+    let mut slug = String::new();
+
+    for c in summary.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug: String = slug.chars().take(MAX_SLUG_LENGTH).collect();
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        String::from("idea")
+    } else {
+        slug.to_string()
+    }
+}
+
+// Appends an entry to the file, separated from the existing content by an empty line
+fn append_to_file(file_path: &Path, entry: &str) -> io::Result<()> {
+    let contents = fs::read_to_string(file_path)?;
+
+    let separator = if contents.is_empty() || contents.ends_with("\n\n") {
+        ""
+    } else if contents.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+
+    let mut file = fs::OpenOptions::new().append(true).open(file_path)?;
+    file.write_all(format!("{}{}", separator, entry).as_bytes())
+}
+
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod tests {
+    use crate::idea::{Idea, Status};
+    use crate::storage::{read_ideas, read_stored_ideas, replace_idea, save_idea, slugify, Layout};
+    use chrono::DateTime;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn idea(summary: &str, captured: &str) -> Idea {
+        Idea {
+            id: None,
+            summary: summary.to_string(),
+            body: String::from("some-body"),
+            captured: Some(DateTime::parse_from_rfc3339(captured).unwrap()),
+            status: Status::Open,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_storage__layout__from_str() {
+        assert_eq!("single-file".parse::<Layout>().unwrap(), Layout::SingleFile);
+        assert_eq!("per-idea".parse::<Layout>().unwrap(), Layout::PerIdea);
+        assert!("something-else".parse::<Layout>().is_err());
+    }
+
+    #[test]
+    fn test_storage__slugify() {
+        assert_eq!(
+            slugify("Some Idea: with (symbols)!"),
+            "some-idea-with-symbols"
+        );
+        assert_eq!(slugify("Ünïcode 🚀"), "n-code");
+        assert_eq!(slugify("!!!"), "idea");
+        assert_eq!(slugify(&"a".repeat(60)).len(), 50);
+    }
+
+    #[test]
+    fn test_storage__save_idea__single_file() {
+        let repo_dir = TempDir::new().unwrap();
+        fs::write(repo_dir.path().join("README.md"), "# Ideas").unwrap();
+        let idea = idea("some-summary", "2023-04-01T12:30:00+02:00");
+
+        let actual = save_idea(repo_dir.path(), Layout::SingleFile, true, &idea).unwrap();
+        let contents = fs::read_to_string(repo_dir.path().join("README.md")).unwrap();
+
+        assert_eq!(actual, vec![PathBuf::from("README.md")]);
+        assert_eq!(contents, format!("# Ideas\n\n{}", idea.render()));
+    }
+
+    #[test]
+    fn test_storage__save_idea__per_idea() {
+        let repo_dir = TempDir::new().unwrap();
+        let idea = idea("Some Summary", "2023-04-01T12:30:00+02:00");
+
+        let first = save_idea(repo_dir.path(), Layout::PerIdea, false, &idea).unwrap();
+        let second = save_idea(repo_dir.path(), Layout::PerIdea, false, &idea).unwrap();
+
+        let expected_path = PathBuf::from("ideas/2023/04/2023-04-01-some-summary.md");
+        assert_eq!(first, vec![expected_path.clone()]);
+        assert_eq!(
+            second,
+            vec![PathBuf::from("ideas/2023/04/2023-04-01-some-summary-2.md")]
+        );
+        assert_eq!(
+            fs::read_to_string(repo_dir.path().join(expected_path)).unwrap(),
+            idea.render()
+        );
+        assert!(!repo_dir.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn test_storage__save_idea__per_idea_with_index() {
+        let repo_dir = TempDir::new().unwrap();
+        let older = idea("older-summary", "2023-03-31T12:30:00+02:00");
+        let newer = idea("newer-summary", "2023-04-01T12:30:00+02:00");
+
+        save_idea(repo_dir.path(), Layout::PerIdea, true, &older).unwrap();
+        let actual = save_idea(repo_dir.path(), Layout::PerIdea, true, &newer).unwrap();
+        let contents = fs::read_to_string(repo_dir.path().join("README.md")).unwrap();
+
+        assert_eq!(
+            actual,
+            vec![
+                PathBuf::from("ideas/2023/04/2023-04-01-newer-summary.md"),
+                PathBuf::from("README.md")
+            ]
+        );
+        assert_eq!(
+            contents,
+            "# Ideas
+
+- 2023-04-01 [newer-summary](ideas/2023/04/2023-04-01-newer-summary.md)
+- 2023-03-31 [older-summary](ideas/2023/03/2023-03-31-older-summary.md)
+"
+        );
+    }
+
+    #[test]
+    fn test_storage__read_ideas__per_idea() {
+        let repo_dir = TempDir::new().unwrap();
+        let older = idea("older-summary", "2022-12-31T12:30:00+02:00");
+        let newer = idea("newer-summary", "2023-04-01T12:30:00+02:00");
+        save_idea(repo_dir.path(), Layout::PerIdea, false, &newer).unwrap();
+        save_idea(repo_dir.path(), Layout::PerIdea, false, &older).unwrap();
+
+        let actual = read_ideas(repo_dir.path(), Layout::PerIdea).unwrap();
+
+        assert_eq!(actual, vec![older, newer]);
+    }
+
+    #[test]
+    fn test_storage__read_ideas__per_idea__same_day() {
+        let repo_dir = TempDir::new().unwrap();
+        let morning = idea("zebra-summary", "2023-04-01T09:00:00+02:00");
+        let evening = idea("apple-summary", "2023-04-01T21:00:00+02:00");
+        let night = idea("zebra-summary", "2023-04-01T23:00:00+02:00");
+        save_idea(repo_dir.path(), Layout::PerIdea, false, &morning).unwrap();
+        save_idea(repo_dir.path(), Layout::PerIdea, false, &evening).unwrap();
+        save_idea(repo_dir.path(), Layout::PerIdea, true, &night).unwrap();
+
+        let actual = read_ideas(repo_dir.path(), Layout::PerIdea).unwrap();
+        let index = fs::read_to_string(repo_dir.path().join("README.md")).unwrap();
+
+        assert_eq!(actual, vec![morning, evening, night]);
+        assert_eq!(
+            index,
+            "# Ideas
+
+- 2023-04-01 [zebra-summary](ideas/2023/04/2023-04-01-zebra-summary-2.md)
+- 2023-04-01 [apple-summary](ideas/2023/04/2023-04-01-apple-summary.md)
+- 2023-04-01 [zebra-summary](ideas/2023/04/2023-04-01-zebra-summary.md)
+"
+        );
+    }
+
+    #[test]
+    fn test_storage__replace_idea__single_file() {
+        let repo_dir = TempDir::new().unwrap();
+        let first = idea("first-summary", "2023-03-31T12:30:00+02:00");
+        let second = idea("second-summary", "2023-04-01T12:30:00+02:00");
+        fs::write(repo_dir.path().join("README.md"), "# Ideas").unwrap();
+        save_idea(repo_dir.path(), Layout::SingleFile, false, &first).unwrap();
+        save_idea(repo_dir.path(), Layout::SingleFile, false, &second).unwrap();
+
+        let stored = read_stored_ideas(repo_dir.path(), Layout::SingleFile).unwrap();
+        let done = Idea {
+            status: Status::Done,
+            ..first.clone()
+        };
+        let actual = replace_idea(
+            repo_dir.path(),
+            Layout::SingleFile,
+            false,
+            &stored[0],
+            &done,
+        );
+        let contents = fs::read_to_string(repo_dir.path().join("README.md")).unwrap();
+
+        assert_eq!(actual.unwrap(), vec![PathBuf::from("README.md")]);
+        assert_eq!(
+            contents,
+            format!("# Ideas\n\n{}\n{}", done.render(), second.render())
+        );
+    }
+
+    #[test]
+    fn test_storage__replace_idea__per_idea_with_index() {
+        let repo_dir = TempDir::new().unwrap();
+        let first = idea("first-summary", "2023-04-01T12:30:00+02:00");
+        save_idea(repo_dir.path(), Layout::PerIdea, true, &first).unwrap();
+
+        let stored = read_stored_ideas(repo_dir.path(), Layout::PerIdea).unwrap();
+        let renamed = Idea {
+            summary: String::from("renamed-summary"),
+            ..first
+        };
+        let actual = replace_idea(repo_dir.path(), Layout::PerIdea, true, &stored[0], &renamed);
+        let idea_path = PathBuf::from("ideas/2023/04/2023-04-01-first-summary.md");
+
+        assert_eq!(
+            actual.unwrap(),
+            vec![idea_path.clone(), PathBuf::from("README.md")]
+        );
+        assert_eq!(
+            fs::read_to_string(repo_dir.path().join(idea_path)).unwrap(),
+            renamed.render()
+        );
+        assert!(fs::read_to_string(repo_dir.path().join("README.md"))
+            .unwrap()
+            .contains("[renamed-summary]"));
+    }
+
+    #[test]
+    fn test_storage__read_ideas__per_idea__no_ideas_yet() {
+        let repo_dir = TempDir::new().unwrap();
+
+        let actual = read_ideas(repo_dir.path(), Layout::PerIdea).unwrap();
+
+        assert!(actual.is_empty());
+    }
+}